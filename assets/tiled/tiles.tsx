@@ -0,0 +1,43 @@
+<?xml version="1.0" encoding="UTF-8"?>
+<tileset version="1.10" name="tiles" tilewidth="64" tileheight="64" tilecount="13" columns="0">
+ <grid orientation="orthogonal" width="1" height="1"/>
+ <tile id="0">
+  <image width="64" height="64" source="../textures/path.png"/>
+ </tile>
+ <tile id="1">
+  <image width="64" height="64" source="../textures/start.png"/>
+ </tile>
+ <tile id="2">
+  <image width="64" height="64" source="../textures/goal.png"/>
+ </tile>
+ <tile id="3">
+  <image width="64" height="64" source="../textures/button.png"/>
+ </tile>
+ <tile id="4">
+  <image width="64" height="64" source="../textures/wall_center.png"/>
+ </tile>
+ <tile id="5">
+  <image width="64" height="64" source="../textures/wall_single_top.png"/>
+ </tile>
+ <tile id="6">
+  <image width="64" height="64" source="../textures/wall_single_bottom.png"/>
+ </tile>
+ <tile id="7">
+  <image width="64" height="64" source="../textures/wall_single_left.png"/>
+ </tile>
+ <tile id="8">
+  <image width="64" height="64" source="../textures/wall_single_right.png"/>
+ </tile>
+ <tile id="9">
+  <image width="64" height="64" source="../textures/wall_corner_top_left.png"/>
+ </tile>
+ <tile id="10">
+  <image width="64" height="64" source="../textures/wall_corner_top_right.png"/>
+ </tile>
+ <tile id="11">
+  <image width="64" height="64" source="../textures/wall_corner_bottom_left.png"/>
+ </tile>
+ <tile id="12">
+  <image width="64" height="64" source="../textures/wall_corner_bottom_right.png"/>
+ </tile>
+</tileset>
@@ -38,7 +38,7 @@ pub enum EditorState {
     MainMenu,
     CreateLevel,
     LoadLevel(Vec<(LevelTemplate, PathBuf)>),
-    Editor(Editor, Option<GameApp>),
+    Editor(Box<Editor>, Option<Box<GameApp>>),
 }
 
 pub struct Editor {
@@ -1,9 +1,8 @@
-use derive_macros::*;
 use nannou::prelude::*;
 use nannou_egui::Egui;
 
 use crate::game::TileTextureIndex;
-use crate::{game::GameState, gui::*};
+use crate::gui::*;
 use learning_conrod_core::gui::{Application, TextureMap};
 
 pub struct GameApp {
@@ -11,27 +10,6 @@ pub struct GameApp {
     pub(crate) current_menu: MenuState,
 }
 
-#[derive(Bounded)]
-pub enum Action {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Action {
-    pub fn perform(&self, state: &mut GameState) {
-        if let GameState::GameState { position, .. } = state {
-            match self {
-                Action::Up => position.y -= 0.5 / 64.0,
-                Action::Down => position.y += 0.5 / 64.0,
-                Action::Left => position.x -= 0.5 / 64.0,
-                Action::Right => position.x += 0.5 / 64.0,
-            }
-        }
-    }
-}
-
 impl Application<'_> for GameApp {
     type ViewResult = ();
     type RawEventResult = ();
@@ -1,26 +1,53 @@
-use color::PLAYER_COLOR;
 pub use level::*;
-use nannou::prelude::*;
-use nannou_egui::Egui;
+use serde::{Deserialize, Serialize};
+pub use simulation::{Action, InputSet, MovementMode};
+use std::fmt::{Display, Formatter};
 use std::rc::Rc;
 
-use learning_conrod_core::gui::TextureMap;
-use log::trace;
-
+pub mod camera;
+pub mod collision;
 pub mod color;
+pub mod entity;
+pub mod grid;
+pub mod inventory;
 pub mod level;
+pub mod level_text;
+pub mod logic;
+pub mod migration;
+pub mod progress;
+pub mod render;
+pub mod replay;
+pub mod secret;
+pub mod simulation;
+pub mod surface;
 pub mod test_level;
+pub mod tiled;
+pub mod undo;
+pub mod validation;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PlayerCoordinate {
     pub x: f32,
     pub y: f32,
+    // the floor the player is on, floors are only changed by climbing ladders
+    pub z: i64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MoveCounter {
+    // tiles entered, undone moves are not counted
+    pub moves: u32,
+    pub undos: u32,
 }
 
 #[derive(Clone, Debug)]
 pub enum GameState {
     Won {
         level_template: Rc<level::LevelTemplate>,
+        result: progress::RunResult,
+    },
+    Lost {
+        level_template: Rc<level::LevelTemplate>,
     },
     GameState {
         //current angle of the rotating square
@@ -32,9 +59,32 @@ pub enum GameState {
         position: PlayerCoordinate,
         old_position: ObjectCoordinate,
 
+        //time not yet consumed by a simulation tick, in seconds
+        tick_accumulator: f32,
+        //actions held during the last tick, used to detect key presses
+        previous_inputs: InputSet,
+
+        //ticks since the level was started
+        elapsed: u32,
+
+        //snapshots taken before every move, for undoing them
+        history: undo::History,
+        moves: MoveCounter,
+
+        //keys picked up so far
+        inventory: inventory::Inventory,
+
+        //ticks until teleporters work again after teleporting
+        teleport_cooldown: u32,
+        //the direction the player slides in while on ice
+        sliding: Option<Direction>,
+
+        //the move in progress when the level uses grid movement
+        grid: Box<grid::GridMovement>,
+
         //current level
         level_template: Rc<level::LevelTemplate>,
-        level_state: level::LevelState,
+        level_state: Box<level::LevelState>,
     },
 }
 
@@ -43,6 +93,7 @@ impl From<&PlayerCoordinate> for ObjectCoordinate {
         ObjectCoordinate {
             x: player.x.round() as i64,
             y: player.y.round() as i64,
+            z: player.z,
         }
     }
 }
@@ -53,78 +104,79 @@ impl From<&mut PlayerCoordinate> for ObjectCoordinate {
     }
 }
 
-impl GameState {
-    pub fn new(level: Rc<level::LevelTemplate>) -> GameState {
-        GameState::GameState {
-            // Rotation for the square.
-            rotation: 0.0,
-            show_hud: true,
-            position: PlayerCoordinate { x: 0.0, y: 0.0 },
-            old_position: ObjectCoordinate { x: 0, y: 0 },
-
-            level_state: level.init_state.clone(),
-            level_template: level,
-        }
-    }
+// the reasons a level cannot be started
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpawnError {
+    NoStart {
+        level_name: String,
+    },
+    MultipleStarts {
+        level_name: String,
+        starts: Vec<ObjectCoordinate>,
+    },
+}
 
-    pub fn handle_input(&mut self) {
-        if let GameState::GameState {
-            position,
-            old_position,
-            level_state,
-            ..
-        } = self
-        {
-            let new_pos: ObjectCoordinate = position.into();
-            if *old_position != new_pos {
-                *old_position = new_pos;
-                trace! {"Stepping on {:?} with {:?}", old_position, position}
-                if let Some(fun) = level_state
-                    .tile_map
-                    .get_mut(old_position)
-                    .and_then(TileType::step_on)
-                {
-                    fun(self);
-                }
+impl Display for SpawnError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            SpawnError::NoStart { level_name } => {
+                write!(f, "Level {level_name} has no Start tile!")
             }
+            SpawnError::MultipleStarts { level_name, starts } => write!(
+                f,
+                "Level {level_name} has {} Start tiles, but only one is allowed: {starts:?}",
+                starts.len()
+            ),
         }
     }
+}
 
-    pub fn draw_game(
-        &self,
-        app: &App,
-        frame: &Frame,
-        egui: &Egui,
-        texture_map: &TextureMap<TileTextureIndex>,
-    ) {
-        match self {
-            GameState::GameState { level_state, .. } => {
-                let draw = app.draw();
-
-                for (coord, tile) in &level_state.tile_map {
-                    tile.draw_tile(&draw, texture_map, coord, self);
-                }
-
-                self.draw_player(&draw, texture_map);
-
-                draw.to_frame(app, frame).unwrap();
+impl GameState {
+    /// Starts the level with the player on its Start tile.
+    ///
+    /// Fails if the level does not have exactly one Start tile.
+    pub fn new(level: Rc<level::LevelTemplate>) -> Result<GameState, SpawnError> {
+        let spawn = match level.init_state.spawn_points().as_slice() {
+            [] => {
+                return Err(SpawnError::NoStart {
+                    level_name: level.name.clone(),
+                })
             }
-            GameState::Won { level_template: _ } => {
-                egui.draw_to_frame(frame).unwrap();
+            [spawn] => *spawn,
+            starts => {
+                return Err(SpawnError::MultipleStarts {
+                    level_name: level.name.clone(),
+                    starts: starts.to_vec(),
+                })
             }
-        }
-    }
+        };
 
-    pub fn draw_player(&self, draw: &Draw, _texture_map: &TextureMap<TileTextureIndex>) {
-        if let GameState::GameState { rotation, .. } = self {
-            draw.rect()
-                .rotate(*rotation)
-                .x_y(-PLAYER_SIZE / 2.0, -PLAYER_SIZE / 2.0)
-                .w_h(PLAYER_SIZE, PLAYER_SIZE)
-                .color(PLAYER_COLOR);
-        }
+        Ok(GameState::GameState {
+            // Rotation for the square.
+            rotation: 0.0,
+            show_hud: true,
+            position: PlayerCoordinate {
+                x: spawn.x as f32,
+                y: spawn.y as f32,
+                z: spawn.z,
+            },
+            old_position: spawn,
+            tick_accumulator: 0.0,
+            previous_inputs: InputSet::new(),
+            elapsed: 0,
+            history: undo::History::default(),
+            moves: MoveCounter::default(),
+            inventory: inventory::Inventory::default(),
+            teleport_cooldown: 0,
+            sliding: None,
+            grid: Box::default(),
+
+            level_state: Box::new(level.init_state.clone()),
+            level_template: level,
+        })
     }
 }
 
 pub const TILE_SIZE: f32 = 64.0;
 pub const PLAYER_SIZE: f32 = 45.0;
+pub const SHARK_SIZE: f32 = 48.0;
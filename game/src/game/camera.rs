@@ -0,0 +1,130 @@
+use crate::game::{LevelState, PlayerCoordinate, TILE_SIZE};
+
+// how quickly the camera catches up with the player, higher is faster
+pub const FOLLOW_RATE: f32 = 8.0;
+
+pub const MIN_ZOOM: f32 = 0.25;
+pub const MAX_ZOOM: f32 = 4.0;
+// the zoom factor applied per line scrolled with the mouse wheel
+pub const ZOOM_STEP: f32 = 1.1;
+
+// the area the camera may show, in tiles
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+}
+
+impl Bounds {
+    // the bounding box around all tiles of the level, `None` for an empty level
+    pub fn of_level(level_state: &LevelState) -> Option<Bounds> {
+        let mut coords = level_state.tile_map.keys();
+        let first = coords.next()?;
+        let mut bounds = Bounds {
+            min_x: first.x as f32,
+            min_y: first.y as f32,
+            max_x: first.x as f32,
+            max_y: first.y as f32,
+        };
+        for coord in coords {
+            bounds.min_x = bounds.min_x.min(coord.x as f32);
+            bounds.min_y = bounds.min_y.min(coord.y as f32);
+            bounds.max_x = bounds.max_x.max(coord.x as f32);
+            bounds.max_y = bounds.max_y.max(coord.y as f32);
+        }
+        // tiles are centered on their coordinate
+        bounds.min_x -= 0.5;
+        bounds.min_y -= 0.5;
+        bounds.max_x += 0.5;
+        bounds.max_y += 0.5;
+        Some(bounds)
+    }
+}
+
+/// The view onto the level, all world drawing goes through [`Camera::to_screen`].
+///
+/// The camera follows the player smoothly and never shows more than necessary
+/// outside of its bounds.
+#[derive(Clone, Debug)]
+pub struct Camera {
+    // the point in the center of the screen, in tiles
+    pub x: f32,
+    pub y: f32,
+    pub zoom: f32,
+    pub bounds: Option<Bounds>,
+}
+
+impl Camera {
+    // a camera looking at the center of its bounds
+    pub fn new(bounds: Option<Bounds>) -> Self {
+        let (x, y) = match bounds {
+            Some(bounds) => (
+                (bounds.min_x + bounds.max_x) / 2.0,
+                (bounds.min_y + bounds.max_y) / 2.0,
+            ),
+            None => (0.0, 0.0),
+        };
+        Camera {
+            x,
+            y,
+            zoom: 1.0,
+            bounds,
+        }
+    }
+
+    // moves the camera onto `target` without following it smoothly
+    pub fn jump_to(&mut self, target: &PlayerCoordinate) {
+        self.x = target.x;
+        self.y = target.y;
+    }
+
+    // the size of a tile on screen, in pixels
+    pub fn tile_size(&self) -> f32 {
+        TILE_SIZE * self.zoom
+    }
+
+    // the screen position of a point in the level, y points up on screen but down in the level
+    pub fn to_screen(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.x) * self.tile_size(),
+            (self.y - y) * self.tile_size(),
+        )
+    }
+
+    /// Moves the camera `dt` seconds closer to `target` and keeps it within its bounds.
+    ///
+    /// `view_w` and `view_h` are the size of the window in pixels.
+    pub fn follow(&mut self, target: &PlayerCoordinate, dt: f32, view_w: f32, view_h: f32) {
+        // framerate independent exponential smoothing
+        let t = 1.0 - (-FOLLOW_RATE * dt).exp();
+        self.x += (target.x - self.x) * t;
+        self.y += (target.y - self.y) * t;
+        self.clamp(view_w, view_h);
+    }
+
+    // zooms in for positive and out for negative steps
+    pub fn zoom_by(&mut self, steps: f32) {
+        self.zoom = (self.zoom * ZOOM_STEP.powf(steps)).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    // keeps the view inside the bounds, a view larger than the bounds is centered on them
+    pub fn clamp(&mut self, view_w: f32, view_h: f32) {
+        let Some(bounds) = self.bounds else {
+            return;
+        };
+        let half_w = view_w / self.tile_size() / 2.0;
+        let half_h = view_h / self.tile_size() / 2.0;
+        self.x = clamp_axis(self.x, bounds.min_x, bounds.max_x, half_w);
+        self.y = clamp_axis(self.y, bounds.min_y, bounds.max_y, half_h);
+    }
+}
+
+fn clamp_axis(center: f32, min: f32, max: f32, half_view: f32) -> f32 {
+    if max - min <= 2.0 * half_view {
+        (min + max) / 2.0
+    } else {
+        center.clamp(min + half_view, max - half_view)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::game::{
+    Direction, LevelState, ObjectCoordinate, PlayerCoordinate, PLAYER_SIZE, TILE_SIZE,
+};
+use std::ops::RangeInclusive;
+
+// half the edge length of the players bounding box measured in tiles
+pub const PLAYER_HALF_EXTENT: f32 = PLAYER_SIZE / TILE_SIZE / 2.0;
+
+// boxes only count as overlapping when they penetrate by more than this,
+// so a player resting against a wall can still slide along it
+const EPSILON: f32 = 1e-4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Axis {
+    X,
+    Y,
+}
+
+impl Axis {
+    fn coordinate(self, along: i64, across: i64, floor: i64) -> ObjectCoordinate {
+        match self {
+            Axis::X => ObjectCoordinate {
+                x: along,
+                y: across,
+                z: floor,
+            },
+            Axis::Y => ObjectCoordinate {
+                x: across,
+                y: along,
+                z: floor,
+            },
+        }
+    }
+
+    // positive y points south, as the tile map is drawn with y pointing down
+    fn direction(self, delta: f32) -> Direction {
+        match (self, delta > 0.0) {
+            (Axis::X, true) => Direction::EAST,
+            (Axis::X, false) => Direction::WEST,
+            (Axis::Y, true) => Direction::SOUTH,
+            (Axis::Y, false) => Direction::NORTH,
+        }
+    }
+}
+
+// the tiles along one axis that are overlapped by the interval [min, max],
+// tile i covers the interval [i - 0.5, i + 0.5]
+fn tile_span(min: f32, max: f32) -> RangeInclusive<i64> {
+    let lo = (min + EPSILON - 0.5).floor() as i64 + 1;
+    let hi = (max - EPSILON + 0.5).ceil() as i64 - 1;
+    lo..=hi
+}
+
+fn span_around(center: f32) -> RangeInclusive<i64> {
+    tile_span(center - PLAYER_HALF_EXTENT, center + PLAYER_HALF_EXTENT)
+}
+
+/// Moves the player by `dx` tiles along the x-axis and then `dy` tiles along the y-axis,
+/// only tiles on the players floor are considered.
+///
+/// Every axis is resolved on its own, so running into a wall diagonally slides along it.
+/// Solid tiles block the players whole bounding box, while directional tiles like OneWay
+/// are checked when the center of the player crosses from one tile into the next.
+/// Returns the tiles that blocked the movement, which is empty if the player moved freely.
+pub fn move_player(
+    position: &mut PlayerCoordinate,
+    dx: f32,
+    dy: f32,
+    level_state: &LevelState,
+) -> Vec<ObjectCoordinate> {
+    let mut blockers = move_axis(position, Axis::X, dx, level_state);
+    blockers.extend(move_axis(position, Axis::Y, dy, level_state));
+    blockers
+}
+
+fn move_axis(
+    position: &mut PlayerCoordinate,
+    axis: Axis,
+    delta: f32,
+    level_state: &LevelState,
+) -> Vec<ObjectCoordinate> {
+    if delta == 0.0 {
+        return vec![];
+    }
+
+    let floor = position.z;
+    let (along, across) = match axis {
+        Axis::X => (position.x, position.y),
+        Axis::Y => (position.y, position.x),
+    };
+
+    let old_span = span_around(along);
+    let new_span = span_around(along + delta);
+
+    // only tiles the player is about to enter can block,
+    // a player that is already stuck inside a solid tile may still leave it
+    let entering: Vec<i64> = if delta > 0.0 {
+        (old_span.end() + 1..=*new_span.end()).collect()
+    } else {
+        (*new_span.start()..*old_span.start()).rev().collect()
+    };
+
+    // the solid tiles on the first line that contains any
+    let mut blockers: Vec<ObjectCoordinate> = entering
+        .into_iter()
+        .map(|line| {
+            span_around(across)
+                .map(|cross| axis.coordinate(line, cross, floor))
+                .filter(|coord| level_state.is_solid(coord))
+                .collect::<Vec<_>>()
+        })
+        .find(|solid| !solid.is_empty())
+        .unwrap_or_default();
+
+    let blocker = blockers.first().map(|coord| match axis {
+        Axis::X => coord.x,
+        Axis::Y => coord.y,
+    });
+
+    let mut new_along = match blocker {
+        Some(line) if delta > 0.0 => (line as f32 - 0.5 - PLAYER_HALF_EXTENT).max(along),
+        Some(line) => (line as f32 + 0.5 + PLAYER_HALF_EXTENT).min(along),
+        None => along + delta,
+    };
+
+    let direction = axis.direction(delta);
+    let step = if delta > 0.0 { 1 } else { -1 };
+    let cross = across.round() as i64;
+    let target = new_along.round() as i64;
+    let mut line = along.round() as i64;
+
+    while line != target {
+        let from = axis.coordinate(line, cross, floor);
+        let to = axis.coordinate(line + step, cross, floor);
+        if !level_state.can_move(&from, &to, direction) {
+            // stop right before the center would round to the next tile
+            new_along = line as f32 + step as f32 * (0.5 - EPSILON);
+            blockers = vec![to];
+            break;
+        }
+        line += step;
+    }
+
+    match axis {
+        Axis::X => position.x = new_along,
+        Axis::Y => position.y = new_along,
+    }
+
+    blockers
+}
@@ -6,3 +6,6 @@ pub const MISSING_TEXTURE: Color = PURPLE;
 pub const IN_GAME_BACKGROUND: Color = BLACK;
 pub const MENU_BACKGROUND: Color = PINK;
 pub const PLAYER_COLOR: Color = RED;
+
+// opacity of the shade drawn over the floor below the player
+pub const LOWER_FLOOR_SHADE: f32 = 0.6;
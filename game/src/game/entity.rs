@@ -0,0 +1,119 @@
+use crate::game::simulation::TICK_DURATION;
+use crate::game::{
+    Direction, LevelState, ObjectCoordinate, Orientation, PlayerCoordinate, TileTextureIndex,
+    PLAYER_SIZE, SHARK_SIZE, TILE_SIZE,
+};
+use serde::{Deserialize, Serialize};
+
+// objects that sit on top of the tile map and can be moved around
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Entity {
+    // pushed by the player, blocks movement and holds down the button it rests on
+    Rock,
+}
+
+impl Entity {
+    pub fn texture_id(&self) -> TileTextureIndex {
+        match self {
+            Entity::Rock => TileTextureIndex::Rock,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Shark {
+    pub position: PlayerCoordinate,
+    pub facing: Direction,
+    // in tiles per second
+    #[serde(default = "Shark::default_speed")]
+    pub speed: f32,
+    pub movement: SharkMovement,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SharkMovement {
+    // swims to the waypoints in order, starting over after the last one
+    Patrol {
+        waypoints: Vec<ObjectCoordinate>,
+        #[serde(default)]
+        next: usize,
+    },
+    // swims straight ahead and turns around where it can not move on, see LevelState::can_move
+    Bounce,
+}
+
+impl Shark {
+    fn default_speed() -> f32 {
+        2.0
+    }
+
+    pub fn texture_id(&self) -> TileTextureIndex {
+        match self.facing {
+            Direction::NORTH | Direction::SOUTH => TileTextureIndex::Shark {
+                orientation: Orientation::Vertical,
+            },
+            _ => TileTextureIndex::Shark {
+                orientation: Orientation::Horizontal,
+            },
+        }
+    }
+
+    pub fn update(&mut self, level_state: &LevelState) {
+        let step = self.speed * TICK_DURATION;
+        match &mut self.movement {
+            SharkMovement::Patrol { waypoints, next } => {
+                let Some(target) = waypoints.get(*next % waypoints.len().max(1)) else {
+                    return;
+                };
+                let dx = target.x as f32 - self.position.x;
+                let dy = target.y as f32 - self.position.y;
+                let distance = (dx * dx + dy * dy).sqrt();
+
+                if distance <= step {
+                    self.position.x = target.x as f32;
+                    self.position.y = target.y as f32;
+                    self.position.z = target.z;
+                    *next = (*next + 1) % waypoints.len();
+                } else {
+                    self.position.x += dx / distance * step;
+                    self.position.y += dy / distance * step;
+                    self.facing = match (dx.abs() >= dy.abs(), dx > 0.0, dy > 0.0) {
+                        (true, true, _) => Direction::EAST,
+                        (true, false, _) => Direction::WEST,
+                        (false, _, true) => Direction::SOUTH,
+                        (false, _, false) => Direction::NORTH,
+                    };
+                }
+            }
+            SharkMovement::Bounce => {
+                // sharks stay on their floor
+                if matches!(self.facing, Direction::UP | Direction::DOWN) {
+                    return;
+                }
+                let (dx, dy) = self.facing.offset();
+                // the tile just in front of the sharks nose
+                let ahead = PlayerCoordinate {
+                    x: self.position.x + dx * (0.5 + step),
+                    y: self.position.y + dy * (0.5 + step),
+                    z: self.position.z,
+                };
+                let ahead = ObjectCoordinate::from(&ahead);
+                let here = ObjectCoordinate::from(&self.position);
+                if ahead != here && !level_state.can_move(&here, &ahead, self.facing) {
+                    self.facing = self.facing.inverted();
+                } else {
+                    self.position.x += dx * step;
+                    self.position.y += dy * step;
+                }
+            }
+        }
+    }
+
+    // whether the shark overlaps the players bounding box
+    pub fn touches(&self, player: &PlayerCoordinate) -> bool {
+        let reach = (SHARK_SIZE + PLAYER_SIZE) / TILE_SIZE / 2.0;
+        self.position.z == player.z
+            && (self.position.x - player.x).abs() < reach
+            && (self.position.y - player.y).abs() < reach
+    }
+}
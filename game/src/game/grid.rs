@@ -0,0 +1,92 @@
+use crate::game::simulation::{InputSet, TICK_RATE};
+use crate::game::{Direction, GameState, ObjectCoordinate, PlayerCoordinate};
+use log::trace;
+
+// how many ticks a move from one tile to the next takes in grid movement
+pub const GRID_MOVE_TICKS: u32 = TICK_RATE / 6;
+
+// a move between two neighbouring tiles that is in progress
+#[derive(Clone, Debug)]
+pub struct Tween {
+    pub from: ObjectCoordinate,
+    pub to: ObjectCoordinate,
+    pub elapsed: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GridMovement {
+    pub tween: Option<Tween>,
+    // the last direction pressed while a move was in progress, started once it finishes
+    pub buffered: Option<Direction>,
+}
+
+impl Tween {
+    fn position(&self) -> PlayerCoordinate {
+        let t = self.elapsed as f32 / GRID_MOVE_TICKS as f32;
+        // ease in and out
+        let t = t * t * (3.0 - 2.0 * t);
+        PlayerCoordinate {
+            x: self.from.x as f32 + (self.to.x - self.from.x) as f32 * t,
+            y: self.from.y as f32 + (self.to.y - self.from.y) as f32 * t,
+            z: self.to.z,
+        }
+    }
+}
+
+impl GameState {
+    // every key press moves the player exactly one tile
+    pub(crate) fn grid_move(&mut self, pressed: &InputSet) {
+        let surface = self.surface_direction();
+        let GameState::GameState { grid, sliding, .. } = self else {
+            return;
+        };
+
+        if let Some(direction) = pressed.iter().rev().find_map(|action| action.direction()) {
+            grid.buffered = Some(direction);
+        }
+        // sliding over ice ignores input, conveyors only move the player when there is no other move
+        let start = match (&grid.tween, sliding) {
+            (Some(_), _) => None,
+            (None, Some(_)) => surface,
+            (None, None) => grid.buffered.take().or(surface),
+        };
+
+        if let Some(direction) = start {
+            self.bump(direction);
+        }
+
+        let GameState::GameState {
+            position,
+            level_state,
+            grid,
+            sliding,
+            ..
+        } = self
+        else {
+            return;
+        };
+
+        if let Some(direction) = start {
+            let from = ObjectCoordinate::from(&*position);
+            let to = from.neighbor(direction);
+            if level_state.can_move(&from, &to, direction) {
+                trace!("Moving from {:?} to {:?}", from, to);
+                grid.tween = Some(Tween {
+                    from,
+                    to,
+                    elapsed: 0,
+                });
+            } else {
+                *sliding = None;
+            }
+        }
+
+        if let Some(tween) = &mut grid.tween {
+            tween.elapsed += 1;
+            *position = tween.position();
+            if tween.elapsed >= GRID_MOVE_TICKS {
+                grid.tween = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use crate::game::KeyColor;
+use std::collections::BTreeMap;
+
+// the items the player has picked up during the current attempt
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Inventory {
+    pub keys: BTreeMap<KeyColor, u32>,
+}
+
+impl Inventory {
+    pub fn add_key(&mut self, color: KeyColor) {
+        *self.keys.entry(color).or_default() += 1;
+    }
+
+    pub fn has_key(&self, color: KeyColor) -> bool {
+        self.keys.get(&color).is_some_and(|count| *count > 0)
+    }
+
+    // removes one key of the color, returns whether there was one
+    pub fn take_key(&mut self, color: KeyColor) -> bool {
+        match self.keys.get_mut(&color) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                true
+            }
+            Some(_) => {
+                self.keys.remove(&color);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -1,25 +1,237 @@
-use nannou::Draw;
-
 use derive_macros::*;
 use derive_macros_helpers::*;
 
-use crate::game::{GameState, TILE_SIZE};
-use learning_conrod_core::gui::TextureMap;
+use crate::game::entity::{Entity, Shark};
+use crate::game::inventory::Inventory;
+use crate::game::logic::{LogicNetwork, LogicState};
+use crate::game::migration::LEVEL_VERSION;
+use crate::game::progress::{Par, RunResult};
+use crate::game::simulation::DEFAULT_SPEED;
+use crate::game::{GameState, MovementMode};
 use log::{error, trace};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
-
-use super::color::MISSING_TEXTURE;
+use std::collections::{BTreeMap, BTreeSet};
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct LevelTemplate {
+    // see migration::LEVEL_VERSION, files without a version predate versioning
+    #[serde(default)]
+    pub version: u32,
     pub name: String,
     pub init_state: LevelState,
+    #[serde(default)]
+    pub logic: LogicNetwork,
+    // levels are played in ascending order, ties are broken by name
+    #[serde(default)]
+    pub order: i32,
+    #[serde(default)]
+    pub movement: MovementMode,
+    // how fast the player moves in free movement, in tiles per second
+    #[serde(default = "LevelTemplate::default_speed")]
+    pub speed: f32,
+    #[serde(default)]
+    pub par: Par,
+}
+
+impl LevelTemplate {
+    pub fn new(name: impl Into<String>, init_state: LevelState) -> Self {
+        LevelTemplate {
+            version: LEVEL_VERSION,
+            name: name.into(),
+            init_state,
+            logic: LogicNetwork::default(),
+            order: 0,
+            movement: MovementMode::default(),
+            speed: DEFAULT_SPEED,
+            par: Par::default(),
+        }
+    }
+
+    fn default_speed() -> f32 {
+        DEFAULT_SPEED
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct LevelState {
     pub tile_map: BTreeMap<ObjectCoordinate, TileType>,
+    #[serde(default)]
+    pub logic_state: LogicState,
+    #[serde(default)]
+    pub entities: BTreeMap<ObjectCoordinate, Entity>,
+    #[serde(default)]
+    pub sharks: Vec<Shark>,
+}
+
+impl LevelState {
+    // the Start tiles of the level, a single player spawns on the only one
+    pub fn spawn_points(&self) -> Vec<ObjectCoordinate> {
+        self.tile_map
+            .iter()
+            .filter(|(_, tile)| matches!(tile, TileType::Start))
+            .map(|(coord, _)| *coord)
+            .collect()
+    }
+
+    // ladders connect floors, so both ends of a climb have to be a ladder
+    pub fn can_climb(&self, from: &ObjectCoordinate, direction: Direction) -> bool {
+        let to = from.neighbor(direction);
+        matches!(self.tile_map.get(from), Some(TileType::Ladder))
+            && matches!(self.tile_map.get(&to), Some(TileType::Ladder))
+    }
+
+    // presses pressure plates that are occupied and counts down timed buttons,
+    // a rock resting on a button holds it pressed regardless of its kind
+    pub fn update_buttons(&mut self, occupied: &BTreeSet<ObjectCoordinate>) {
+        for (coord, tile) in self.tile_map.iter_mut() {
+            if let TileType::Button { pressed, kind, .. } = tile {
+                let weighted = matches!(self.entities.get(coord), Some(Entity::Rock));
+                match kind {
+                    ButtonKind::Toggle => *pressed |= weighted,
+                    ButtonKind::PressurePlate => *pressed = weighted || occupied.contains(coord),
+                    ButtonKind::Timed {
+                        duration,
+                        remaining,
+                    } => {
+                        if weighted {
+                            *remaining = *duration;
+                        }
+                        *pressed = *remaining > 0;
+                        *remaining = remaining.saturating_sub(1);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn update_sharks(&mut self) {
+        let mut sharks = std::mem::take(&mut self.sharks);
+        for shark in &mut sharks {
+            shark.update(self);
+        }
+        self.sharks = sharks;
+    }
+
+    // where the teleporter at `from` sends the player, if the destination exists
+    pub fn teleport_exit(&self, from: &ObjectCoordinate) -> Option<ObjectCoordinate> {
+        let Some(TileType::Teleporter { destination, .. }) = self.tile_map.get(from) else {
+            return None;
+        };
+        match destination {
+            TeleportDestination::Coordinate(coord) => {
+                self.tile_map.contains_key(coord).then_some(*coord)
+            }
+            TeleportDestination::Channel(channel) => {
+                let on_channel = |(coord, tile): (&ObjectCoordinate, &TileType)| match tile {
+                    TileType::Teleporter {
+                        destination: TeleportDestination::Channel(other),
+                        ..
+                    } if other == channel && coord != from => Some(*coord),
+                    _ => None,
+                };
+                // wrap around to the first one after the last
+                self.tile_map
+                    .range(from..)
+                    .find_map(on_channel)
+                    .or_else(|| self.tile_map.range(..from).find_map(on_channel))
+            }
+        }
+    }
+
+    // a closed door can be unlocked if the inventory holds a key of its color
+    pub fn can_unlock(&self, coord: &ObjectCoordinate, inventory: &Inventory) -> bool {
+        match self.tile_map.get(coord) {
+            Some(TileType::Door {
+                color, open: false, ..
+            }) => inventory.has_key(*color),
+            _ => false,
+        }
+    }
+
+    pub fn unlock(&mut self, coord: &ObjectCoordinate, inventory: &mut Inventory) -> bool {
+        if !self.can_unlock(coord, inventory) {
+            return false;
+        }
+        if let Some(TileType::Door {
+            color,
+            open,
+            consumes,
+        }) = self.tile_map.get_mut(coord)
+        {
+            trace!("Unlocking the {:?} door at {:?}", color, coord);
+            if *consumes {
+                inventory.take_key(*color);
+            }
+            *open = true;
+        }
+        true
+    }
+
+    // the kind of wall that connects to the walls around `coord`,
+    // fake walls count as walls, as they are meant to blend in
+    pub fn expected_wall_type(&self, coord: &ObjectCoordinate) -> WallType {
+        WallType::from_neighbours(|direction| {
+            matches!(
+                self.tile_map.get(&coord.neighbor(direction)),
+                Some(TileType::Wall { .. } | TileType::FakeWall { .. })
+            )
+        })
+    }
+
+    pub fn is_solid(&self, coord: &ObjectCoordinate) -> bool {
+        self.entities.contains_key(coord)
+            || self.tile_map.get(coord).is_some_and(TileType::is_solid)
+    }
+
+    /// Whether the player, a rock or a shark may move from `from` onto the neighbouring `to`.
+    ///
+    /// Missing tiles are open like paths, only solid tiles, entities and directional tiles block.
+    pub fn can_move(
+        &self,
+        from: &ObjectCoordinate,
+        to: &ObjectCoordinate,
+        direction: Direction,
+    ) -> bool {
+        !self.is_solid(to) && self.can_pass(from, to, direction)
+    }
+
+    // an entity can be pushed one tile further if it can move there
+    pub fn can_push(&self, from: &ObjectCoordinate, direction: Direction) -> bool {
+        self.entities.contains_key(from)
+            && self.can_move(from, &from.neighbor(direction), direction)
+    }
+
+    // pushes the entity at `from` one tile further, returns whether it moved
+    pub fn push_entity(&mut self, from: &ObjectCoordinate, direction: Direction) -> bool {
+        if !self.can_push(from, direction) {
+            return false;
+        }
+        let to = from.neighbor(direction);
+        match self.entities.remove(from) {
+            Some(entity) => {
+                trace!("Pushing {:?} from {:?} to {:?}", entity, from, to);
+                self.entities.insert(to, entity);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // whether the player may move from one tile onto a neighbouring tile in the given direction
+    pub fn can_pass(
+        &self,
+        from: &ObjectCoordinate,
+        to: &ObjectCoordinate,
+        direction: Direction,
+    ) -> bool {
+        self.tile_map
+            .get(from)
+            .is_none_or(|tile| tile.allows_movement(direction))
+            && self
+                .tile_map
+                .get(to)
+                .is_none_or(|tile| tile.allows_movement(direction))
+    }
 }
 
 #[derive(
@@ -47,6 +259,17 @@ impl Direction {
         }
     }
 
+    // the movement in the x and y plane, floors are changed by climbing instead
+    pub fn offset(self) -> (f32, f32) {
+        match self {
+            Direction::NORTH => (0.0, -1.0),
+            Direction::SOUTH => (0.0, 1.0),
+            Direction::WEST => (-1.0, 0.0),
+            Direction::EAST => (1.0, 0.0),
+            Direction::UP | Direction::DOWN => (0.0, 0.0),
+        }
+    }
+
     pub fn file_modifier(self) -> &'static str {
         match self {
             Direction::UP => "lower",
@@ -133,6 +356,47 @@ pub enum WallType {
 }
 
 impl WallType {
+    /// The kind of wall that connects to the neighbouring walls in the given directions.
+    ///
+    /// Walls face their open sides, the sides without a neighbouring wall.
+    pub fn from_neighbours(is_wall: impl Fn(Direction) -> bool) -> WallType {
+        let (walls, open): (Vec<Direction>, Vec<Direction>) = [
+            Direction::NORTH,
+            Direction::EAST,
+            Direction::SOUTH,
+            Direction::WEST,
+        ]
+        .into_iter()
+        .partition(|direction| is_wall(*direction));
+
+        match (walls.as_slice(), open.as_slice()) {
+            ([], _) => WallType::Lone,
+            ([wall], _) => WallType::End {
+                facing: wall.inverted(),
+            },
+            (_, []) => WallType::Center,
+            (_, [facing]) => WallType::Single { facing: *facing },
+            ([Direction::NORTH, Direction::SOUTH], _) => WallType::Double {
+                orientation: Orientation::Vertical,
+            },
+            ([Direction::EAST, Direction::WEST], _) => WallType::Double {
+                orientation: Orientation::Horizontal,
+            },
+            _ => WallType::Corner {
+                north_south_facing: if walls.contains(&Direction::NORTH) {
+                    NorthSouthAxis::South
+                } else {
+                    NorthSouthAxis::North
+                },
+                east_west_facing: if walls.contains(&Direction::EAST) {
+                    EastWestAxis::West
+                } else {
+                    EastWestAxis::East
+                },
+            },
+        }
+    }
+
     pub fn file_modifier(self) -> String {
         match self {
             WallType::Lone => "rock".to_string(),
@@ -162,10 +426,18 @@ pub enum TileTextureIndex {
     Goal { active: bool },
     Gate { open: bool, facing: Direction },
     OneWay { facing: Direction },
-    Button { pressed: bool },
+    Button { pressed: bool, style: ButtonStyle },
+    Rock,
+    Shark { orientation: Orientation },
+    Key { color: KeyColor },
+    Door { color: KeyColor, open: bool },
+    Teleporter { active: bool },
+    Ice,
+    Conveyor { facing: Direction },
+    FakeWall,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TileType {
     Wall {
         kind: WallType,
@@ -180,6 +452,8 @@ pub enum TileType {
         open: bool,
         facing: Direction,
         hidden: GateVisibility,
+        #[serde(default)]
+        reveal: RevealTrigger,
     },
     OneWay {
         inverted: bool,
@@ -188,18 +462,160 @@ pub enum TileType {
     Button {
         pressed: bool,
         inverted: bool,
-        target: ObjectCoordinate,
+        targets: Vec<ObjectCoordinate>,
+        #[serde(default)]
+        kind: ButtonKind,
+    },
+    // picked up when stepped on, leaving a Path behind
+    Key {
+        color: KeyColor,
+    },
+    // a closed door opens when the player walks into it with a key of its color,
+    // using up the key if `consumes` is set
+    Door {
+        color: KeyColor,
+        open: bool,
+        #[serde(default = "TileType::default_consumes")]
+        consumes: bool,
+    },
+    // the player slides over ice in the direction they entered it, until something blocks them
+    Ice,
+    // carries the player in the direction it is facing, a button reverses it
+    Conveyor {
+        facing: Direction,
+        #[serde(default)]
+        inverted: bool,
+    },
+    // moves the player to its destination when stepped on while active
+    Teleporter {
+        destination: TeleportDestination,
+        active: bool,
+    },
+    // looks like a wall until revealed, but the player can walk through it
+    FakeWall {
+        kind: WallType,
+        #[serde(default)]
+        reveal: RevealTrigger,
+        #[serde(default)]
+        revealed: bool,
     },
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TeleportDestination {
+    Coordinate(ObjectCoordinate),
+    // the next other teleporter on the same channel, in coordinate order
+    Channel(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ButtonKind {
+    // flips between pressed and released every time it is stepped on
+    #[default]
+    Toggle,
+    // only pressed while something stands on it
+    PressurePlate,
+    // pressed for `duration` ticks after being stepped on, `remaining` counts down to release
+    Timed {
+        duration: u32,
+        remaining: u32,
+    },
+}
+
+impl ButtonKind {
+    pub fn style(self) -> ButtonStyle {
+        match self {
+            ButtonKind::Toggle => ButtonStyle::Toggle,
+            ButtonKind::PressurePlate => ButtonStyle::PressurePlate,
+            ButtonKind::Timed { .. } => ButtonStyle::Timed,
+        }
+    }
+}
+
+#[derive(
+    Debug, Ord, PartialOrd, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, Bounded, Enumerable,
+)]
+pub enum ButtonStyle {
+    Toggle,
+    PressurePlate,
+    Timed,
+}
+
+#[derive(
+    Debug, Ord, PartialOrd, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, Bounded, Enumerable,
+)]
+pub enum KeyColor {
+    Red,
+    Green,
+    Blue,
+    Yellow,
+}
+
+impl KeyColor {
+    pub fn file_modifier(self) -> &'static str {
+        match self {
+            KeyColor::Red => "red",
+            KeyColor::Green => "green",
+            KeyColor::Blue => "blue",
+            KeyColor::Yellow => "yellow",
+        }
+    }
+}
+
+impl ButtonStyle {
+    pub fn file_modifier(self) -> &'static str {
+        match self {
+            ButtonStyle::Toggle => "button",
+            ButtonStyle::PressurePlate => "pressure_plate",
+            ButtonStyle::Timed => "timed_button",
+        }
+    }
+}
+
 impl TileType {
+    fn default_consumes() -> bool {
+        true
+    }
+
+    // whether apply_button can change the state of this tile
+    pub fn accepts_signal(&self) -> bool {
+        matches!(
+            self,
+            TileType::Goal { .. }
+                | TileType::Gate { .. }
+                | TileType::OneWay { .. }
+                | TileType::Teleporter { .. }
+                | TileType::Conveyor { .. }
+                | TileType::FakeWall { .. }
+        )
+    }
+
     pub fn apply_button(&mut self, active: bool) {
         match self {
             TileType::Goal {
                 active: active_goal,
             } => *active_goal = active,
-            TileType::Gate { open, .. } => *open = active,
-            TileType::OneWay { inverted, .. } => *inverted = active,
+            TileType::Gate {
+                open,
+                hidden,
+                reveal,
+                ..
+            } => {
+                *open = active;
+                if active && *reveal == RevealTrigger::Button {
+                    *hidden = GateVisibility::Visible;
+                }
+            }
+            TileType::FakeWall {
+                reveal, revealed, ..
+            } => *revealed |= active && *reveal == RevealTrigger::Button,
+            TileType::OneWay { inverted, .. } | TileType::Conveyor { inverted, .. } => {
+                *inverted = active
+            }
+            TileType::Teleporter {
+                active: active_teleporter,
+                ..
+            } => *active_teleporter = active,
             _ => error!(
                 "Tried to change the state of a single State Tile or Button Tile with a Button!"
             ),
@@ -211,27 +627,52 @@ impl TileType {
             TileType::Goal { active: true } => {
                 trace!("Goal reached!");
                 Some(Box::new(|game| {
-                    if let GameState::GameState { level_template, .. } = game {
+                    if let GameState::GameState {
+                        level_template,
+                        level_state,
+                        elapsed,
+                        moves,
+                        ..
+                    } = game
+                    {
+                        let secrets = level_state.secrets();
                         *game = GameState::Won {
                             level_template: level_template.clone(),
+                            result: RunResult {
+                                ticks: *elapsed,
+                                moves: moves.moves,
+                                secrets_found: secrets.values().filter(|found| **found).count(),
+                                secrets: secrets.len(),
+                            },
                         }
                     }
                 }))
             }
-            TileType::Button {
-                pressed,
-                inverted,
-                target,
-            } => {
+            TileType::Button { pressed, kind, .. } => {
                 trace!("Stepping on a Button");
-                *pressed = !*pressed;
-                let power = *pressed ^ *inverted;
-                let t = *target;
-                Some(Box::new(move |game: &mut GameState| {
-                    if let GameState::GameState { level_state, .. } = game {
-                        if let Some(tile) = level_state.tile_map.get_mut(&t) {
-                            tile.apply_button(power)
-                        }
+                // the targets are updated by the logic network on every tick
+                match kind {
+                    ButtonKind::Toggle => *pressed = !*pressed,
+                    // pressure plates are updated by LevelState::update_buttons
+                    ButtonKind::PressurePlate => {}
+                    ButtonKind::Timed {
+                        duration,
+                        remaining,
+                    } => {
+                        *pressed = true;
+                        *remaining = *duration;
+                    }
+                }
+                None
+            }
+            TileType::Teleporter { active: true, .. } => Some(Box::new(|game| game.teleport())),
+            TileType::Key { color } => {
+                trace!("Picking up a {:?} key", color);
+                let color = *color;
+                *self = TileType::Path;
+                Some(Box::new(move |game| {
+                    if let GameState::GameState { inventory, .. } = game {
+                        inventory.add_key(color);
                     }
                 }))
             }
@@ -239,29 +680,7 @@ impl TileType {
         }
     }
 
-    pub fn draw_tile(
-        &self,
-        draw: &Draw,
-        texture_map: &TextureMap<TileTextureIndex>,
-        coord: &ObjectCoordinate,
-        state: &GameState,
-    ) {
-        if let GameState::GameState { position, .. } = state {
-            let x = (coord.x as f32) * TILE_SIZE - position.x * 64.0 - TILE_SIZE / 2.0;
-            let y = (-coord.y as f32) * TILE_SIZE + position.y * 64.0 - TILE_SIZE / 2.0;
-
-            if let Some(texture) = texture_map.get(&self.tile_texture_id()) {
-                draw.texture(texture).x_y(x, y).w_h(TILE_SIZE, TILE_SIZE);
-            } else {
-                draw.rect()
-                    .x_y(x, y)
-                    .w_h(TILE_SIZE, TILE_SIZE)
-                    .color(MISSING_TEXTURE);
-            }
-        }
-    }
-
-    //TODO directionality for OneWay
+    // directional tiles like OneWay are not solid, see allows_movement
     pub fn is_solid(&self) -> bool {
         match self {
             TileType::Wall { .. } => true,
@@ -272,6 +691,80 @@ impl TileType {
             TileType::Gate { open, .. } => !open,
             TileType::OneWay { .. } => false,
             TileType::Ladder => false,
+            TileType::Key { .. } => false,
+            TileType::Door { open, .. } => !open,
+            TileType::Teleporter { .. } => false,
+            TileType::Ice => false,
+            TileType::Conveyor { .. } => false,
+            TileType::FakeWall { .. } => false,
+        }
+    }
+
+    // fake walls and hidden gates that can be revealed, see RevealTrigger
+    pub fn is_secret(&self) -> bool {
+        match self {
+            TileType::FakeWall { .. } => true,
+            TileType::Gate { reveal, .. } => *reveal != RevealTrigger::Never,
+            _ => false,
+        }
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        matches!(
+            self,
+            TileType::FakeWall {
+                revealed: false,
+                ..
+            } | TileType::Gate {
+                hidden: GateVisibility::Hidden(_),
+                ..
+            }
+        )
+    }
+
+    pub fn reveal_trigger(&self) -> RevealTrigger {
+        match self {
+            TileType::FakeWall { reveal, .. } | TileType::Gate { reveal, .. } => *reveal,
+            _ => RevealTrigger::Never,
+        }
+    }
+
+    pub fn reveal(&mut self) {
+        match self {
+            TileType::FakeWall { revealed, .. } => *revealed = true,
+            TileType::Gate { hidden, .. } => *hidden = GateVisibility::Visible,
+            _ => {}
+        }
+    }
+
+    // entering and leaving a tile follow the same rule, a OneWay only allows moving along its facing
+    pub fn allows_movement(&self, direction: Direction) -> bool {
+        match self {
+            TileType::OneWay { .. } => self.effective_facing() == Some(direction),
+            _ => true,
+        }
+    }
+
+    // the direction a directional tile currently points to, taking inversion by a button into account
+    pub fn effective_facing(&self) -> Option<Direction> {
+        match self {
+            TileType::OneWay {
+                facing,
+                inverted: false,
+            }
+            | TileType::Conveyor {
+                facing,
+                inverted: false,
+            } => Some(*facing),
+            TileType::OneWay {
+                facing,
+                inverted: true,
+            }
+            | TileType::Conveyor {
+                facing,
+                inverted: true,
+            } => Some(facing.inverted()),
+            _ => None,
         }
     }
 
@@ -281,7 +774,10 @@ impl TileType {
             TileType::Start => TileTextureIndex::Start,
             TileType::Ladder => TileTextureIndex::Ladder,
             TileType::Goal { active } => TileTextureIndex::Goal { active: *active },
-            TileType::Button { pressed, .. } => TileTextureIndex::Button { pressed: *pressed },
+            TileType::Button { pressed, kind, .. } => TileTextureIndex::Button {
+                pressed: *pressed,
+                style: kind.style(),
+            },
             TileType::OneWay {
                 facing,
                 inverted: false,
@@ -293,10 +789,31 @@ impl TileType {
                 facing: facing.inverted(),
             },
             TileType::Wall { kind } => TileTextureIndex::Wall { kind: *kind },
+            TileType::Key { color } => TileTextureIndex::Key { color: *color },
+            TileType::Teleporter { active, .. } => TileTextureIndex::Teleporter { active: *active },
+            TileType::Ice => TileTextureIndex::Ice,
+            TileType::FakeWall {
+                kind,
+                revealed: false,
+                ..
+            } => TileTextureIndex::Wall { kind: *kind },
+            TileType::FakeWall { .. } => TileTextureIndex::FakeWall,
+            TileType::Conveyor { facing, inverted } => TileTextureIndex::Conveyor {
+                facing: if *inverted {
+                    facing.inverted()
+                } else {
+                    *facing
+                },
+            },
+            TileType::Door { color, open, .. } => TileTextureIndex::Door {
+                color: *color,
+                open: *open,
+            },
             TileType::Gate {
                 open,
                 facing,
                 hidden: GateVisibility::Visible,
+                ..
             }
             | TileType::Gate {
                 open: open @ true,
@@ -310,6 +827,7 @@ impl TileType {
                 open: false,
                 facing: _,
                 hidden: GateVisibility::Hidden(mimic),
+                ..
             } => mimic.tile_texture_id(),
         }
     }
@@ -332,8 +850,31 @@ impl TileTextureIndex {
             TileTextureIndex::Ladder => "ladder".to_string(),
             TileTextureIndex::OneWay { facing } => format!("one_way_{}", facing.file_modifier()),
             TileTextureIndex::Wall { kind } => format!("wall_{}", kind.file_modifier()),
-            TileTextureIndex::Button { pressed } => {
-                format!("button{}", if *pressed { "_pressed" } else { "" })
+            TileTextureIndex::Rock => "rock".to_string(),
+            TileTextureIndex::Shark { orientation } => match orientation {
+                Orientation::Horizontal => "shark_left".to_string(),
+                Orientation::Vertical => "shark_up".to_string(),
+            },
+            TileTextureIndex::Key { color } => format!("key_{}", color.file_modifier()),
+            TileTextureIndex::Ice => "ice".to_string(),
+            TileTextureIndex::FakeWall => "fake_wall".to_string(),
+            TileTextureIndex::Conveyor { facing } => {
+                format!("conveyor_{}", facing.file_modifier())
+            }
+            TileTextureIndex::Teleporter { active } => {
+                format!("teleporter{}", if !active { "_inactive" } else { "" })
+            }
+            TileTextureIndex::Door { color, open } => format!(
+                "door_{}{}",
+                color.file_modifier(),
+                if *open { "_open" } else { "" }
+            ),
+            TileTextureIndex::Button { pressed, style } => {
+                format!(
+                    "{}{}",
+                    style.file_modifier(),
+                    if *pressed { "_pressed" } else { "" }
+                )
             }
         }
     }
@@ -349,6 +890,29 @@ impl ToString for TileTextureIndex {
 pub struct ObjectCoordinate {
     pub x: i64,
     pub y: i64,
+    // the floor, levels without floors only use floor 0
+    #[serde(default)]
+    pub z: i64,
+}
+
+impl ObjectCoordinate {
+    // the adjacent coordinate in the given direction, north is towards negative y
+    pub fn neighbor(self, direction: Direction) -> ObjectCoordinate {
+        let ObjectCoordinate { x, y, z } = self;
+        match direction {
+            Direction::UP => ObjectCoordinate { x, y, z: z + 1 },
+            Direction::DOWN => ObjectCoordinate { x, y, z: z - 1 },
+            Direction::NORTH => ObjectCoordinate { x, y: y - 1, z },
+            Direction::SOUTH => ObjectCoordinate { x, y: y + 1, z },
+            Direction::EAST => ObjectCoordinate { x: x + 1, y, z },
+            Direction::WEST => ObjectCoordinate { x: x - 1, y, z },
+        }
+    }
+
+    // the direction towards an adjacent coordinate
+    pub fn direction_to(self, other: ObjectCoordinate) -> Option<Direction> {
+        Direction::enumerate_all().find(|direction| self.neighbor(*direction) == other)
+    }
 }
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
@@ -359,14 +923,34 @@ pub struct Connections {
     pub right: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum GateVisibility {
     Visible,
     Hidden(Box<TileType>),
 }
 
+// how a secret gives itself away, once revealed it stays visible
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RevealTrigger {
+    // a hidden gate still shows itself while it is open
+    #[default]
+    Never,
+    // the player comes within `distance` tiles on the same floor
+    Proximity {
+        distance: u32,
+    },
+    // the player walks into it, fake walls are also revealed by walking through them
+    Bump,
+    // a button sends it a signal
+    Button,
+}
+
 pub mod saving {
+    use crate::game::level_text;
+    use crate::game::migration::LEVEL_VERSION;
+    use crate::game::tiled::TiledError;
     use crate::game::LevelTemplate;
+    use serde::Serialize;
     use std::fmt::{Display, Formatter};
     use std::fs::File;
     use std::io::Write;
@@ -376,6 +960,7 @@ pub mod saving {
     pub enum SavingError {
         IO(std::io::Error),
         Serialize(ron::Error),
+        Tiled(TiledError),
     }
 
     impl Display for SavingError {
@@ -383,6 +968,7 @@ pub mod saving {
             match self {
                 SavingError::IO(err) => Display::fmt(err, f),
                 SavingError::Serialize(err) => Display::fmt(err, f),
+                SavingError::Tiled(err) => Display::fmt(err, f),
             }
         }
     }
@@ -399,10 +985,7 @@ pub mod saving {
         }
     }
 
-    pub(crate) fn save_level(
-        path: &std::path::Path,
-        level: &LevelTemplate,
-    ) -> Result<(), SavingError> {
+    pub fn to_ron<T: Serialize>(value: &T) -> Result<String, ron::Error> {
         let pretty = ron::ser::PrettyConfig::default()
             .depth_limit(!0)
             .new_line("\n".into())
@@ -410,8 +993,11 @@ pub mod saving {
             .separate_tuple_members(false)
             .enumerate_arrays(false);
 
-        let out = ron::ser::to_string_pretty(level, pretty)?;
+        ron::ser::to_string_pretty(value, pretty)
+    }
 
+    // creates the folders leading to `path` as needed
+    pub fn write_file(path: &std::path::Path, content: &str) -> Result<(), SavingError> {
         if let Some(parent) = path.parent() {
             //path does not exist try to create it
             if !parent.exists() {
@@ -419,24 +1005,66 @@ pub mod saving {
             }
         }
 
-        let mut file = File::create(path)?;
+        File::create(path)?.write_all(content.as_bytes())?;
+        Ok(())
+    }
 
-        info!("Writing level {} to {:?}.", level.name, path);
+    // always writes the current version of the level format,
+    // as text if the file has the extension of the text format
+    pub(crate) fn save_level(
+        path: &std::path::Path,
+        level: &LevelTemplate,
+    ) -> Result<(), SavingError> {
+        let level = &LevelTemplate {
+            version: LEVEL_VERSION,
+            ..level.clone()
+        };
+        let out = if level_text::is_text_level(path) {
+            level_text::to_text(level)?
+        } else {
+            to_ron(level)?
+        };
 
-        file.write_all(out.as_bytes())?;
-        Ok(())
+        info!("Writing level {} to {:?}.", level.name, path);
+        write_file(path, &out)
     }
 }
 
 pub mod loading {
+    use crate::game::level_text;
+    use crate::game::migration::{self, MigrationError, LEVEL_VERSION};
+    use crate::game::tiled::{self, TiledError};
     use crate::game::LevelTemplate;
+    use std::fmt::{Display, Formatter};
     use std::fs::File;
     use std::io::Read;
 
+    #[derive(Debug)]
     pub enum LoadingError {
         IO(std::io::Error),
         Deserialize(ron::de::Error),
         Spanned(ron::error::SpannedError),
+        Migration(MigrationError),
+        // a problem in the grid of a text level, see level_text
+        Grid { line: usize, message: String },
+        Tiled(TiledError),
+    }
+
+    impl Display for LoadingError {
+        fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+            match self {
+                LoadingError::IO(err) => Display::fmt(err, f),
+                LoadingError::Deserialize(err) => Display::fmt(err, f),
+                LoadingError::Spanned(err) => write!(
+                    f,
+                    "line {}, column {}: {}",
+                    err.position.line, err.position.col, err.code
+                ),
+                LoadingError::Migration(err) => Display::fmt(err, f),
+                LoadingError::Grid { line, message } => write!(f, "line {line}: {message}"),
+                LoadingError::Tiled(err) => Display::fmt(err, f),
+            }
+        }
     }
 
     impl From<std::io::Error> for LoadingError {
@@ -457,42 +1085,145 @@ pub mod loading {
         }
     }
 
-    pub fn load_levels(asset_path: &std::path::Path) -> Result<Vec<LevelTemplate>, LoadingError> {
+    impl From<MigrationError> for LoadingError {
+        fn from(migration_err: MigrationError) -> Self {
+            LoadingError::Migration(migration_err)
+        }
+    }
+
+    // a file in a folder of levels or replays that could not be loaded
+    #[derive(Debug)]
+    pub struct BrokenFile {
+        pub file_name: String,
+        pub error: LoadingError,
+    }
+
+    #[derive(Debug)]
+    pub struct Directory<T> {
+        pub loaded: Vec<T>,
+        // ordered by file name
+        pub broken: Vec<BrokenFile>,
+    }
+
+    impl<T> Default for Directory<T> {
+        fn default() -> Self {
+            Directory {
+                loaded: Vec::new(),
+                broken: Vec::new(),
+            }
+        }
+    }
+
+    pub fn read_file(path: &std::path::Path) -> Result<String, LoadingError> {
+        let mut content = String::new();
+        File::open(path)?.read_to_string(&mut content)?;
+        Ok(content)
+    }
+
+    /// Loads every file in the folder at `path` that `accepts` lets through,
+    /// in the order of their file names.
+    ///
+    /// Files that fail to load are logged and kept as [`BrokenFile`]s.
+    pub fn load_directory<T>(
+        path: &std::path::Path,
+        accepts: impl Fn(&std::path::Path) -> bool,
+        load: impl Fn(&std::path::Path) -> Result<T, LoadingError>,
+    ) -> Result<Directory<T>, LoadingError> {
+        let mut directory = Directory::default();
+        if !path.exists() {
+            return Ok(directory);
+        }
+
+        let mut entries: Vec<_> = path.read_dir()?.flatten().collect();
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let path = entry.path();
+            if !entry.file_type().is_ok_and(|f_type| f_type.is_file()) || !accepts(&path) {
+                continue;
+            }
+            match load(&path) {
+                Ok(value) => directory.loaded.push(value),
+                Err(error) => {
+                    log::error!("Failed to load '{}': {}", path.display(), error);
+                    directory.broken.push(BrokenFile {
+                        file_name: entry.file_name().to_string_lossy().into_owned(),
+                        error,
+                    })
+                }
+            }
+        }
+        Ok(directory)
+    }
+
+    // levels are sorted by their order, levels of the same order by name
+    pub fn level_order(level: &LevelTemplate) -> (i32, &str) {
+        (level.order, &level.name)
+    }
+
+    /// The level following `current` in the level order of `levels`, which have to be sorted by it.
+    ///
+    /// `current` does not have to be among `levels`, for example when its file was removed.
+    pub fn next_level<'a, L: std::borrow::Borrow<LevelTemplate>>(
+        levels: &'a [L],
+        current: &LevelTemplate,
+    ) -> Option<&'a L> {
+        levels
+            .iter()
+            .find(|level| level_order(level.borrow()) > level_order(current))
+    }
+
+    // the loaded levels are in level order
+    pub fn load_levels(
+        asset_path: &std::path::Path,
+    ) -> Result<Directory<LevelTemplate>, LoadingError> {
         log::info!("Loading Levels!");
         let path = asset_path.join("levels");
-        let mut levels = vec![];
 
         if !path.exists() {
             //path does not exist try to create it
             std::fs::create_dir_all(&path)?;
         }
 
-        let dir = path.read_dir()?;
-
-        for entry in dir.flatten() {
-            if let Ok(f_type) = entry.file_type() {
-                if f_type.is_file() {
-                    if let Ok(level) = load_level(entry.path().as_path()) {
-                        levels.push(level);
-                    }
-                }
-            }
-        }
-        log::info!("Loaded {} levels!", levels.len());
-        Ok(levels)
+        // skips files like .gitkeep
+        let is_level = |path: &std::path::Path| {
+            path.extension().is_some_and(|ext| ext == "ron")
+                || level_text::is_text_level(path)
+                || tiled::is_tiled_level(path)
+        };
+        let mut directory = load_directory(&path, is_level, |path| load_level(path, asset_path))?;
+        directory
+            .loaded
+            .sort_by(|a, b| level_order(a).cmp(&level_order(b)));
+        log::info!("Loaded {} levels!", directory.loaded.len());
+        Ok(directory)
     }
 
-    fn load_level(path: &std::path::Path) -> Result<LevelTemplate, LoadingError> {
+    fn load_level(
+        path: &std::path::Path,
+        asset_path: &std::path::Path,
+    ) -> Result<LevelTemplate, LoadingError> {
         log::info!("Loading level at '{}'!", path.display());
-        let mut content = vec![];
-
-        use serde::Deserialize;
+        if tiled::is_tiled_level(path) {
+            let mapping = tiled::load_mapping(&tiled::mapping_path(asset_path))?;
+            return tiled::load_tiled(path, &mapping);
+        }
+        let content = read_file(path)?;
 
-        File::open(path)?.read_to_end(&mut content)?;
+        if level_text::is_text_level(path) {
+            level_text::from_text(&content)
+        } else {
+            parse_level(&content)
+        }
+    }
 
-        let mut des = ron::de::Deserializer::from_bytes(content.as_slice())?;
+    // upgrades levels of older versions before deserializing them
+    pub fn parse_level(content: &str) -> Result<LevelTemplate, LoadingError> {
+        let version = migration::file_version(content)?;
+        let content = migration::migrate(content, version)?;
 
-        let level = LevelTemplate::deserialize(&mut des)?;
+        // errors with a position point authors to the line that broke the level
+        let mut level: LevelTemplate = ron::from_str(&content)?;
+        level.version = LEVEL_VERSION;
         Ok(level)
     }
 }
@@ -0,0 +1,235 @@
+use crate::game::level::loading::{parse_level, LoadingError};
+use crate::game::level::saving::to_ron;
+use crate::game::{
+    ButtonKind, Direction, GateVisibility, KeyColor, LevelState, LevelTemplate, ObjectCoordinate,
+    RevealTrigger, TeleportDestination, TileType, WallType,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+// text levels are named `<name>.level.txt`, other text files like a README are no levels
+pub const SUFFIX: &str = ".level.txt";
+
+pub fn is_text_level(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().ends_with(SUFFIX))
+}
+
+// the character a tile is drawn with in the grid
+fn legend_char(tile: &TileType) -> char {
+    match tile {
+        TileType::Wall { .. } => '#',
+        TileType::FakeWall { .. } => 'F',
+        TileType::Path => '.',
+        TileType::Start => 'S',
+        TileType::Goal { active: true } => 'G',
+        TileType::Goal { active: false } => 'g',
+        TileType::Ladder => 'H',
+        TileType::OneWay { .. } => match tile.effective_facing() {
+            Some(Direction::NORTH) => '^',
+            Some(Direction::SOUTH) => 'v',
+            Some(Direction::WEST) => '<',
+            _ => '>',
+        },
+        TileType::Button { .. } => 'B',
+        TileType::Gate { .. } => '=',
+        TileType::Key { .. } => 'K',
+        TileType::Door { .. } => 'D',
+        TileType::Ice => '~',
+        TileType::Conveyor { .. } => 'C',
+        TileType::Teleporter { .. } => 'T',
+    }
+}
+
+// the tile a character in the grid stands for, walls are connected to their neighbours later
+fn legend_tile(c: char) -> Option<TileType> {
+    let one_way = |facing| TileType::OneWay {
+        inverted: false,
+        facing,
+    };
+    Some(match c {
+        '#' => TileType::Wall {
+            kind: WallType::Lone,
+        },
+        'F' => TileType::FakeWall {
+            kind: WallType::Lone,
+            reveal: RevealTrigger::Never,
+            revealed: false,
+        },
+        '.' => TileType::Path,
+        'S' => TileType::Start,
+        'G' => TileType::Goal { active: true },
+        'g' => TileType::Goal { active: false },
+        'H' => TileType::Ladder,
+        '^' => one_way(Direction::NORTH),
+        'v' => one_way(Direction::SOUTH),
+        '<' => one_way(Direction::WEST),
+        '>' => one_way(Direction::EAST),
+        'B' => TileType::Button {
+            pressed: false,
+            inverted: false,
+            targets: vec![],
+            kind: ButtonKind::Toggle,
+        },
+        '=' => TileType::Gate {
+            open: false,
+            facing: Direction::NORTH,
+            hidden: GateVisibility::Visible,
+            reveal: RevealTrigger::Never,
+        },
+        'K' => TileType::Key {
+            color: KeyColor::Red,
+        },
+        'D' => TileType::Door {
+            color: KeyColor::Red,
+            open: false,
+            consumes: true,
+        },
+        '~' => TileType::Ice,
+        'C' => TileType::Conveyor {
+            facing: Direction::EAST,
+            inverted: false,
+        },
+        'T' => TileType::Teleporter {
+            destination: TeleportDestination::Channel(String::new()),
+            active: true,
+        },
+        _ => return None,
+    })
+}
+
+// the tile a character stands for at `coord`, with walls connected to the walls around them
+fn resolve(c: char, coord: &ObjectCoordinate, level_state: &LevelState) -> Option<TileType> {
+    let mut tile = legend_tile(c)?;
+    if let TileType::Wall { kind } | TileType::FakeWall { kind, .. } = &mut tile {
+        *kind = level_state.expected_wall_type(coord);
+    }
+    Some(tile)
+}
+
+/// Writes a level in the text format, see [`from_text`].
+pub fn to_text(level: &LevelTemplate) -> Result<String, ron::Error> {
+    let level_state = &level.init_state;
+    let mut floors: BTreeMap<i64, Vec<ObjectCoordinate>> = BTreeMap::new();
+    let mut properties = level.clone();
+    properties.init_state.tile_map.clear();
+
+    for (coord, tile) in &level_state.tile_map {
+        floors.entry(coord.z).or_default().push(*coord);
+        if resolve(legend_char(tile), coord, level_state).as_ref() != Some(tile) {
+            properties.init_state.tile_map.insert(*coord, tile.clone());
+        }
+    }
+
+    let mut out = to_ron(&properties)?;
+    out.push('\n');
+    for (z, coords) in floors {
+        let min_x = coords.iter().map(|coord| coord.x).min().unwrap_or_default();
+        let min_y = coords.iter().map(|coord| coord.y).min().unwrap_or_default();
+        let max_x = coords.iter().map(|coord| coord.x).max().unwrap_or_default();
+        let max_y = coords.iter().map(|coord| coord.y).max().unwrap_or_default();
+
+        out.push_str(&format!("floor {z} at {min_x} {min_y}\n"));
+        for y in min_y..=max_y {
+            let row: String = (min_x..=max_x)
+                .map(|x| {
+                    level_state
+                        .tile_map
+                        .get(&ObjectCoordinate { x, y, z })
+                        .map_or(' ', legend_char)
+                })
+                .collect();
+            out.push_str(row.trim_end());
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+/// Reads a level from the text format.
+///
+/// The file starts with the level in RON, whose tile map only holds the tiles
+/// the grid can not describe on its own, like buttons with targets.
+/// Each floor follows as a line `floor <z> at <x> <y>`, naming the coordinate
+/// of its top left character, and one line of characters per row:
+///
+/// `#` wall, `F` fake wall, `.` path, `S` start, `G` goal, `g` inactive goal, `H` ladder,
+/// `^` `v` `<` `>` one way, `B` button, `=` gate, `K` key, `D` door, `~` ice,
+/// `C` conveyor, `T` teleporter and a space for no tile.
+///
+/// Walls are connected to the walls around them.
+pub fn from_text(content: &str) -> Result<LevelTemplate, LoadingError> {
+    let lines: Vec<&str> = content.lines().collect();
+    let grid_start = lines
+        .iter()
+        .position(|line| line.starts_with("floor "))
+        .unwrap_or(lines.len());
+
+    let mut level = parse_level(&lines[..grid_start].join("\n"))?;
+    let tiles = std::mem::take(&mut level.init_state.tile_map);
+
+    // the characters of the grid, resolved once all tiles are known
+    let mut grid = BTreeMap::new();
+    let mut origin = None;
+    let mut floors = BTreeSet::new();
+    for (index, line) in lines.iter().enumerate().skip(grid_start) {
+        let line_number = index + 1;
+        if let Some(floor) = line.strip_prefix("floor ") {
+            let (z, x, y) = parse_floor(floor).ok_or_else(|| LoadingError::Grid {
+                line: line_number,
+                message: format!("expected 'floor <z> at <x> <y>', found '{line}'"),
+            })?;
+            // a second section would silently replace the tiles of the first
+            if !floors.insert(z) {
+                return Err(LoadingError::Grid {
+                    line: line_number,
+                    message: format!("floor {z} is described twice"),
+                });
+            }
+            origin = Some((z, x, y));
+            continue;
+        }
+        let Some((z, x, y)) = &mut origin else {
+            continue;
+        };
+        for (column, c) in line.chars().enumerate() {
+            if c == ' ' {
+                continue;
+            }
+            let coord = ObjectCoordinate {
+                x: *x + column as i64,
+                y: *y,
+                z: *z,
+            };
+            let tile = legend_tile(c).ok_or_else(|| LoadingError::Grid {
+                line: line_number,
+                message: format!("unknown tile '{c}' in column {}", column + 1),
+            })?;
+            level.init_state.tile_map.insert(coord, tile);
+            grid.insert(coord, c);
+        }
+        *y += 1;
+    }
+
+    level.init_state.tile_map.extend(tiles.clone());
+    for (coord, c) in grid {
+        if !tiles.contains_key(&coord) {
+            if let Some(tile) = resolve(c, &coord, &level.init_state) {
+                level.init_state.tile_map.insert(coord, tile);
+            }
+        }
+    }
+    Ok(level)
+}
+
+// parses `<z> at <x> <y>`
+fn parse_floor(floor: &str) -> Option<(i64, i64, i64)> {
+    let mut parts = floor.split_whitespace();
+    let z = parts.next()?.parse().ok()?;
+    if parts.next()? != "at" {
+        return None;
+    }
+    let x = parts.next()?.parse().ok()?;
+    let y = parts.next()?.parse().ok()?;
+    parts.next().is_none().then_some((z, x, y))
+}
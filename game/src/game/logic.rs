@@ -0,0 +1,157 @@
+use crate::game::{LevelState, ObjectCoordinate, TileType};
+use log::{error, trace};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// The wiring between buttons and the tiles they control.
+///
+/// Buttons are the signal sources, every tile for which [`TileType::accepts_signal`] is true is a sink.
+/// A sink without an entry in `sinks` is driven by the OR of all buttons targeting it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LogicNetwork {
+    #[serde(default)]
+    pub nodes: BTreeMap<String, LogicNode>,
+    #[serde(default)]
+    pub sinks: BTreeMap<ObjectCoordinate, Signal>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Signal {
+    Constant(bool),
+    Button(ObjectCoordinate),
+    Node(String),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum LogicNode {
+    And(Vec<Signal>),
+    Or(Vec<Signal>),
+    Xor(Vec<Signal>),
+    Not(Signal),
+    // outputs the input from `ticks` ticks ago, starting out inactive
+    Delay { input: Signal, ticks: u32 },
+}
+
+// the part of the network that changes while playing
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LogicState {
+    // the most recent inputs of every delay node, oldest first
+    pub delays: BTreeMap<String, VecDeque<bool>>,
+}
+
+struct Evaluator<'a> {
+    network: &'a LogicNetwork,
+    buttons: BTreeMap<ObjectCoordinate, bool>,
+    delays: &'a BTreeMap<String, VecDeque<bool>>,
+    cache: BTreeMap<String, bool>,
+    visiting: BTreeSet<String>,
+}
+
+impl Evaluator<'_> {
+    fn signal(&mut self, signal: &Signal) -> bool {
+        match signal {
+            Signal::Constant(value) => *value,
+            Signal::Button(coord) => self.buttons.get(coord).copied().unwrap_or_else(|| {
+                error!("Logic signal refers to {:?}, which is not a Button!", coord);
+                false
+            }),
+            Signal::Node(name) => self.node(name),
+        }
+    }
+
+    fn node(&mut self, name: &str) -> bool {
+        if let Some(value) = self.cache.get(name) {
+            return *value;
+        }
+        let Some(node) = self.network.nodes.get(name) else {
+            error!("Logic signal refers to the unknown node {:?}!", name);
+            return false;
+        };
+        if !self.visiting.insert(name.to_string()) {
+            error!("Logic node {:?} depends on itself without a delay!", name);
+            return false;
+        }
+
+        let value = match node {
+            LogicNode::And(inputs) => inputs.iter().all(|input| self.signal(input)),
+            LogicNode::Or(inputs) => inputs.iter().any(|input| self.signal(input)),
+            LogicNode::Xor(inputs) => inputs
+                .iter()
+                .fold(false, |acc, input| acc ^ self.signal(input)),
+            LogicNode::Not(input) => !self.signal(input),
+            LogicNode::Delay { input, ticks: 0 } => self.signal(input),
+            LogicNode::Delay { ticks, .. } => self
+                .delays
+                .get(name)
+                .filter(|history| history.len() >= *ticks as usize)
+                .and_then(|history| history.front().copied())
+                .unwrap_or(false),
+        };
+
+        self.visiting.remove(name);
+        self.cache.insert(name.to_string(), value);
+        value
+    }
+}
+
+impl LogicNetwork {
+    /// Recomputes the state of every sink from the current button states
+    /// and advances all delay nodes by one tick.
+    pub fn update(&self, level_state: &mut LevelState) {
+        let mut implicit: BTreeMap<ObjectCoordinate, bool> = BTreeMap::new();
+        let mut buttons = BTreeMap::new();
+
+        for (coord, tile) in &level_state.tile_map {
+            if let TileType::Button {
+                pressed,
+                inverted,
+                targets,
+                ..
+            } = tile
+            {
+                let power = *pressed ^ *inverted;
+                buttons.insert(*coord, power);
+                for target in targets {
+                    *implicit.entry(*target).or_default() |= power;
+                }
+            }
+        }
+
+        let mut evaluator = Evaluator {
+            network: self,
+            buttons,
+            delays: &level_state.logic_state.delays,
+            cache: BTreeMap::new(),
+            visiting: BTreeSet::new(),
+        };
+
+        let mut sinks = implicit;
+        for (coord, signal) in &self.sinks {
+            sinks.insert(*coord, evaluator.signal(signal));
+        }
+
+        let mut delay_inputs = BTreeMap::new();
+        for (name, node) in &self.nodes {
+            if let LogicNode::Delay { input, ticks } = node {
+                if *ticks > 0 {
+                    delay_inputs.insert(name.clone(), (evaluator.signal(input), *ticks));
+                }
+            }
+        }
+
+        for (name, (value, ticks)) in delay_inputs {
+            let history = level_state.logic_state.delays.entry(name).or_default();
+            history.push_back(value);
+            while history.len() > ticks as usize {
+                history.pop_front();
+            }
+        }
+
+        for (coord, power) in sinks {
+            match level_state.tile_map.get_mut(&coord) {
+                Some(tile) if tile.accepts_signal() => tile.apply_button(power),
+                _ => trace!("Ignoring logic signal for {:?}", coord),
+            }
+        }
+    }
+}
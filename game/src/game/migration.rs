@@ -0,0 +1,151 @@
+use log::info;
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+
+/// The version of the level format written by `save_level`.
+///
+/// Files without a version are version 0,
+/// older files are upgraded step by step through [`MIGRATIONS`] when loaded.
+pub const LEVEL_VERSION: u32 = 1;
+
+// upgrades the content of a level file by one version, or explains why it could not
+pub type Migration = fn(&str) -> Result<String, String>;
+
+// the migration at index n upgrades a file from version n to n + 1
+pub const MIGRATIONS: [Migration; LEVEL_VERSION as usize] = [single_button_targets];
+
+#[derive(Debug)]
+pub enum MigrationError {
+    // the file was written by a newer version of the game
+    Unsupported { version: u32 },
+    Failed { version: u32, message: String },
+}
+
+impl Display for MigrationError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            MigrationError::Unsupported { version } => write!(
+                f,
+                "level version {version} is newer than the supported version {LEVEL_VERSION}"
+            ),
+            MigrationError::Failed { version, message } => {
+                write!(
+                    f,
+                    "upgrading from level version {version} failed: {message}"
+                )
+            }
+        }
+    }
+}
+
+// only reads the version, every other field is ignored
+#[derive(Deserialize)]
+struct VersionProbe {
+    #[serde(default)]
+    version: u32,
+}
+
+pub fn file_version(content: &str) -> Result<u32, ron::error::SpannedError> {
+    ron::from_str::<VersionProbe>(content).map(|probe| probe.version)
+}
+
+// upgrades the content of a level file of the given version to LEVEL_VERSION
+pub fn migrate(content: &str, version: u32) -> Result<String, MigrationError> {
+    if version > LEVEL_VERSION {
+        return Err(MigrationError::Unsupported { version });
+    }
+
+    let mut content = content.to_string();
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let from = from as u32;
+        content = migration(&content).map_err(|message| MigrationError::Failed {
+            version: from,
+            message,
+        })?;
+        info!("Upgraded level from version {} to {}", from, from + 1);
+    }
+    Ok(content)
+}
+
+// version 1 lets buttons control several tiles, `target: T` becomes `targets: [T]`
+fn single_button_targets(content: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = find_field(rest, "target") {
+        out.push_str(&rest[..start]);
+        let after_name = rest[start + "target".len()..].trim_start();
+        let Some(value) = after_name.strip_prefix(':') else {
+            return Err("expected a ':' after 'target'".to_string());
+        };
+        let value = value.trim_start();
+        let end = value_end(value).ok_or("the value of 'target' is not closed")?;
+        out.push_str("targets: [");
+        out.push_str(&value[..end]);
+        out.push(']');
+        rest = &value[end..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+// the byte offset of the next field called `name`, skipping string literals
+fn find_field(content: &str, name: &str) -> Option<usize> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut previous = ' ';
+
+    for (offset, c) in content.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if !is_ident(previous) && content[offset..].starts_with(name) {
+            let after = &content[offset + name.len()..];
+            if !after.starts_with(is_ident) && after.trim_start().starts_with(':') {
+                return Some(offset);
+            }
+        }
+        previous = c;
+    }
+    None
+}
+
+// the byte offset just past the value at the start of `content`
+fn value_end(content: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (offset, c) in content.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth == 0 => return Some(offset),
+            ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(offset + 1);
+                }
+            }
+            ',' if depth == 0 => return Some(offset),
+            _ => {}
+        }
+    }
+    (depth == 0).then_some(content.len())
+}
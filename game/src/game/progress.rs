@@ -0,0 +1,112 @@
+use crate::game::level::loading::{read_file, LoadingError};
+use crate::game::level::saving::{to_ron, write_file, SavingError};
+use crate::game::simulation::TICK_RATE;
+use crate::game::LevelTemplate;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// how a level was completed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunResult {
+    pub ticks: u32,
+    pub moves: u32,
+    pub secrets_found: usize,
+    pub secrets: usize,
+}
+
+impl RunResult {
+    pub fn seconds(&self) -> f32 {
+        self.ticks as f32 / TICK_RATE as f32
+    }
+}
+
+/// The results a level expects for a perfect run.
+///
+/// Finishing a level earns one star, being within each par value earns another.
+/// A par value that is not set is always met.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Par {
+    // in seconds
+    #[serde(default)]
+    pub time: Option<f32>,
+    #[serde(default)]
+    pub moves: Option<u32>,
+}
+
+impl Par {
+    pub fn stars(&self, result: &RunResult) -> u8 {
+        let fast = self.time.is_none_or(|time| result.seconds() <= time);
+        let short = self.moves.is_none_or(|moves| result.moves <= moves);
+        1 + fast as u8 + short as u8
+    }
+}
+
+// the best values reached in any run, not necessarily in the same run
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BestResult {
+    pub stars: u8,
+    pub ticks: u32,
+    pub moves: u32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Progress {
+    pub best: BTreeMap<String, BestResult>,
+}
+
+impl Progress {
+    // returns whether anything improved
+    pub fn record(&mut self, level_name: &str, result: &RunResult, stars: u8) -> bool {
+        let new = match self.best.get(level_name) {
+            Some(best) => BestResult {
+                stars: best.stars.max(stars),
+                ticks: best.ticks.min(result.ticks),
+                moves: best.moves.min(result.moves),
+            },
+            None => BestResult {
+                stars,
+                ticks: result.ticks,
+                moves: result.moves,
+            },
+        };
+        self.best.insert(level_name.to_string(), new) != Some(new)
+    }
+}
+
+pub fn progress_path(asset_path: &Path) -> PathBuf {
+    asset_path.join("saves").join("progress.ron")
+}
+
+// a missing save file means nothing was completed yet
+pub fn load_progress(path: &Path) -> Result<Progress, LoadingError> {
+    if !path.exists() {
+        return Ok(Progress::default());
+    }
+    Ok(ron::from_str(&read_file(path)?)?)
+}
+
+pub fn save_progress(path: &Path, progress: &Progress) -> Result<(), SavingError> {
+    let out = to_ron(progress)?;
+    info!("Saving progress to {:?}.", path);
+    write_file(path, &out)
+}
+
+/// Keeps the best results of a completed level in the save file at `path`.
+///
+/// A save file that fails to load is left untouched, saving over it would lose all stored results.
+pub fn record_win(path: &Path, level: &LevelTemplate, result: &RunResult) {
+    let mut progress = match load_progress(path) {
+        Ok(progress) => progress,
+        Err(err) => {
+            error!("Failed to load progress, not saving the result: {}", err);
+            return;
+        }
+    };
+    if progress.record(&level.name, result, level.par.stars(result)) {
+        if let Err(err) = save_progress(path, &progress) {
+            error!("Failed to save progress: {}", err);
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::game::camera::Camera;
+use crate::game::color::{LOWER_FLOOR_SHADE, MISSING_TEXTURE, PLAYER_COLOR};
+use crate::game::entity::Shark;
+use crate::game::{
+    Direction, GameState, ObjectCoordinate, TileTextureIndex, TileType, PLAYER_SIZE, SHARK_SIZE,
+};
+use learning_conrod_core::gui::TextureMap;
+use nannou::prelude::*;
+use nannou_egui::Egui;
+
+impl GameState {
+    pub fn draw_game(
+        &self,
+        app: &App,
+        frame: &Frame,
+        egui: &Egui,
+        texture_map: &TextureMap<TileTextureIndex>,
+        camera: &Camera,
+    ) {
+        match self {
+            GameState::GameState {
+                level_state,
+                position,
+                ..
+            } => {
+                let draw = app.draw();
+                let floor = position.z;
+
+                let mut below = level_state
+                    .tile_map
+                    .iter()
+                    .filter(|(coord, _)| coord.z == floor - 1)
+                    .peekable();
+
+                // the floor below is visible through the current floor, but dimmed
+                if below.peek().is_some() {
+                    for (coord, tile) in below {
+                        tile.draw_tile(&draw, texture_map, coord, camera);
+                    }
+                    let window = app.window_rect();
+                    draw.rect()
+                        .w_h(window.w(), window.h())
+                        .rgba(0.0, 0.0, 0.0, LOWER_FLOOR_SHADE);
+                }
+
+                for (coord, tile) in &level_state.tile_map {
+                    if coord.z == floor {
+                        tile.draw_tile(&draw, texture_map, coord, camera);
+                    }
+                }
+
+                for (coord, entity) in &level_state.entities {
+                    if coord.z == floor {
+                        draw_texture(&draw, texture_map, &entity.texture_id(), coord, camera);
+                    }
+                }
+
+                for shark in &level_state.sharks {
+                    if shark.position.z == floor {
+                        shark.draw_shark(&draw, texture_map, camera);
+                    }
+                }
+
+                self.draw_player(&draw, texture_map, camera);
+
+                draw.to_frame(app, frame).unwrap();
+            }
+            GameState::Won { .. } | GameState::Lost { .. } => {
+                egui.draw_to_frame(frame).unwrap();
+            }
+        }
+    }
+
+    pub fn draw_player(
+        &self,
+        draw: &Draw,
+        _texture_map: &TextureMap<TileTextureIndex>,
+        camera: &Camera,
+    ) {
+        if let GameState::GameState {
+            rotation, position, ..
+        } = self
+        {
+            let (x, y) = camera.to_screen(position.x, position.y);
+            let size = PLAYER_SIZE * camera.zoom;
+            draw.rect()
+                .rotate(*rotation)
+                .x_y(x, y)
+                .w_h(size, size)
+                .color(PLAYER_COLOR);
+        }
+    }
+}
+
+impl Shark {
+    pub fn draw_shark(
+        &self,
+        draw: &Draw,
+        texture_map: &TextureMap<TileTextureIndex>,
+        camera: &Camera,
+    ) {
+        let (x, y) = camera.to_screen(self.position.x, self.position.y);
+        let size = SHARK_SIZE * camera.zoom;
+        // the textures face west and north, a negative size mirrors them for the other directions
+        let (w, h) = match self.facing {
+            Direction::EAST => (-size, size),
+            Direction::SOUTH => (size, -size),
+            _ => (size, size),
+        };
+
+        if let Some(texture) = texture_map.get(&self.texture_id()) {
+            draw.texture(texture).x_y(x, y).w_h(w, h);
+        } else {
+            draw.rect().x_y(x, y).w_h(size, size).color(MISSING_TEXTURE);
+        }
+    }
+}
+
+impl TileType {
+    pub fn draw_tile(
+        &self,
+        draw: &Draw,
+        texture_map: &TextureMap<TileTextureIndex>,
+        coord: &ObjectCoordinate,
+        camera: &Camera,
+    ) {
+        draw_texture(draw, texture_map, &self.tile_texture_id(), coord, camera);
+    }
+}
+
+// draws the texture so that it covers the tile at coord
+fn draw_texture(
+    draw: &Draw,
+    texture_map: &TextureMap<TileTextureIndex>,
+    texture_id: &TileTextureIndex,
+    coord: &ObjectCoordinate,
+    camera: &Camera,
+) {
+    let (x, y) = camera.to_screen(coord.x as f32, coord.y as f32);
+    let size = camera.tile_size();
+
+    if let Some(texture) = texture_map.get(texture_id) {
+        draw.texture(texture).x_y(x, y).w_h(size, size);
+    } else {
+        draw.rect().x_y(x, y).w_h(size, size).color(MISSING_TEXTURE);
+    }
+}
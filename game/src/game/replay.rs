@@ -0,0 +1,120 @@
+use crate::game::level::loading::{load_directory, read_file, Directory, LoadingError};
+use crate::game::level::saving::{to_ron, write_file, SavingError};
+use crate::game::{GameState, InputSet, LevelTemplate, SpawnError};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::rc::Rc;
+
+/// A run through a level, stored as the inputs held down during every tick.
+///
+/// Replaying the inputs tick by tick on the same level reproduces the run exactly.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub level_name: String,
+    // see level_hash, a replay only fits the exact level it was recorded on
+    pub level_hash: u64,
+    // inputs together with the number of consecutive ticks they were held for
+    pub inputs: Vec<(u32, InputSet)>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    LevelChanged { level_name: String },
+    Spawn(SpawnError),
+    Serialize(ron::Error),
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ReplayError::LevelChanged { level_name } => write!(
+                f,
+                "the level {level_name} changed since the replay was recorded"
+            ),
+            ReplayError::Spawn(err) => Display::fmt(err, f),
+            ReplayError::Serialize(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+// FNV-1a over the serialized level
+pub fn level_hash(level: &LevelTemplate) -> Result<u64, ron::Error> {
+    let serialized = ron::to_string(level)?;
+    Ok(serialized
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        }))
+}
+
+impl Replay {
+    pub fn new(level: &LevelTemplate) -> Result<Self, ron::Error> {
+        Ok(Replay {
+            level_name: level.name.clone(),
+            level_hash: level_hash(level)?,
+            inputs: Vec::new(),
+        })
+    }
+
+    // records that `inputs` were held down for another `ticks` ticks
+    pub fn record(&mut self, inputs: &InputSet, ticks: u32) {
+        if ticks == 0 {
+            return;
+        }
+        match self.inputs.last_mut() {
+            Some((count, last)) if last == inputs => *count += ticks,
+            _ => self.inputs.push((ticks, inputs.clone())),
+        }
+    }
+
+    pub fn ticks(&self) -> u32 {
+        self.inputs.iter().map(|(ticks, _)| ticks).sum()
+    }
+
+    /// Plays the replay on `level` and returns the resulting state.
+    ///
+    /// Playback stops early once the game is won or lost.
+    pub fn play(&self, level: Rc<LevelTemplate>) -> Result<GameState, ReplayError> {
+        if level_hash(&level).map_err(ReplayError::Serialize)? != self.level_hash {
+            return Err(ReplayError::LevelChanged {
+                level_name: level.name.clone(),
+            });
+        }
+
+        let mut state = GameState::new(level).map_err(ReplayError::Spawn)?;
+        for (ticks, inputs) in &self.inputs {
+            for _ in 0..*ticks {
+                if !matches!(state, GameState::GameState { .. }) {
+                    return Ok(state);
+                }
+                state.tick(inputs);
+            }
+        }
+        Ok(state)
+    }
+}
+
+// where the player saves replays, next to their progress and out of version control
+pub fn replay_path(asset_path: &Path, level_name: &str) -> std::path::PathBuf {
+    asset_path
+        .join("saves")
+        .join("replays")
+        .join(format!("{level_name}.ron"))
+}
+
+pub fn save_replay(path: &Path, replay: &Replay) -> Result<(), SavingError> {
+    let out = to_ron(replay)?;
+    info!("Writing replay of {} to {:?}.", replay.level_name, path);
+    write_file(path, &out)
+}
+
+pub fn load_replay(path: &Path) -> Result<Replay, LoadingError> {
+    Ok(ron::from_str(&read_file(path)?)?)
+}
+
+// all replays in the replays folder below `path`
+pub fn load_replays(path: &Path) -> Result<Directory<Replay>, LoadingError> {
+    load_directory(&path.join("replays"), |_| true, load_replay)
+}
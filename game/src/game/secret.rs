@@ -0,0 +1,44 @@
+use crate::game::{LevelState, ObjectCoordinate, RevealTrigger};
+use log::trace;
+use std::collections::BTreeMap;
+
+impl LevelState {
+    // every secret of the level and whether it has been found
+    pub fn secrets(&self) -> BTreeMap<ObjectCoordinate, bool> {
+        self.tile_map
+            .iter()
+            .filter(|(_, tile)| tile.is_secret())
+            .map(|(coord, tile)| (*coord, !tile.is_hidden()))
+            .collect()
+    }
+
+    // reveals the secrets close to the player and the fake wall they stand in
+    pub fn reveal_near(&mut self, player: &ObjectCoordinate) {
+        for (coord, tile) in self.tile_map.iter_mut() {
+            if !tile.is_hidden() || coord.z != player.z {
+                continue;
+            }
+            let distance = (coord.x - player.x).abs().max((coord.y - player.y).abs());
+            let near = match tile.reveal_trigger() {
+                RevealTrigger::Proximity { distance: range } => distance <= range as i64,
+                _ => false,
+            };
+            if near || (coord == player && tile.is_secret()) {
+                trace!("Revealing the secret at {:?}", coord);
+                tile.reveal();
+            }
+        }
+    }
+
+    // reveals a secret that is revealed by walking into it, returns whether it was revealed
+    pub fn reveal_bumped(&mut self, coord: &ObjectCoordinate) -> bool {
+        match self.tile_map.get_mut(coord) {
+            Some(tile) if tile.is_hidden() && tile.reveal_trigger() == RevealTrigger::Bump => {
+                trace!("Revealing the secret at {:?}", coord);
+                tile.reveal();
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use crate::game::grid::GridMovement;
+use crate::game::undo::Snapshot;
+use crate::game::{collision, Direction, GameState, ObjectCoordinate, PlayerCoordinate, TileType};
+use derive_macros::*;
+use derive_macros_helpers::*;
+use log::{error, trace};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+// the simulation advances in fixed steps of 1 / TICK_RATE seconds
+pub const TICK_RATE: u32 = 60;
+pub const TICK_DURATION: f32 = 1.0 / TICK_RATE as f32;
+
+// longer frames are cut short, so a hiccup does not trigger a flood of ticks
+pub const MAX_FRAME_TIME: f32 = 0.25;
+
+// after teleporting the player has to wait this many ticks before teleporting again
+pub const TELEPORT_COOLDOWN: u32 = TICK_RATE;
+
+// in tiles per second, the speed of the former half a pixel per update at 60 updates per second
+pub const DEFAULT_SPEED: f32 = 0.5 / 64.0 * TICK_RATE as f32;
+
+#[derive(
+    Clone, Copy, Debug, Ord, PartialOrd, Eq, PartialEq, Bounded, Enumerable, Serialize, Deserialize,
+)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Ascend,
+    Descend,
+    Undo,
+}
+
+// the actions held down during a tick
+pub type InputSet = BTreeSet<Action>;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MovementMode {
+    // the player moves freely while a direction is held
+    #[default]
+    Free,
+    // every key press moves the player one tile, see GameState::grid_move
+    Grid,
+}
+
+impl Action {
+    // the direction an action moves the player in, if it is a movement
+    pub fn direction(&self) -> Option<Direction> {
+        match self {
+            Action::Up => Some(Direction::NORTH),
+            Action::Down => Some(Direction::SOUTH),
+            Action::Left => Some(Direction::WEST),
+            Action::Right => Some(Direction::EAST),
+            // climbing only happens when the key is pressed, see GameState::climb
+            Action::Ascend | Action::Descend => None,
+            Action::Undo => None,
+        }
+    }
+}
+
+impl GameState {
+    /// Advances the game by `dt` seconds while `inputs` are held down.
+    ///
+    /// The elapsed time is consumed in ticks of [`TICK_DURATION`],
+    /// any remainder is kept for the next call. Returns the number of ticks that were run.
+    pub fn step(&mut self, inputs: &InputSet, dt: f32) -> u32 {
+        let mut ticks = 0;
+
+        if let GameState::GameState {
+            tick_accumulator, ..
+        } = self
+        {
+            *tick_accumulator += dt.clamp(0.0, MAX_FRAME_TIME);
+        }
+
+        while let GameState::GameState {
+            tick_accumulator, ..
+        } = self
+        {
+            if *tick_accumulator < TICK_DURATION {
+                break;
+            }
+            *tick_accumulator -= TICK_DURATION;
+            self.tick(inputs);
+            ticks += 1;
+        }
+
+        ticks
+    }
+
+    /// Runs a single simulation tick.
+    pub fn tick(&mut self, inputs: &InputSet) {
+        if let GameState::GameState {
+            rotation,
+            teleport_cooldown,
+            elapsed,
+            ..
+        } = self
+        {
+            // Rotate 8 radians per second.
+            *rotation += 8.0 * TICK_DURATION;
+            *elapsed += 1;
+            *teleport_cooldown = teleport_cooldown.saturating_sub(1);
+        } else {
+            return;
+        }
+
+        let pressed: InputSet = match self {
+            GameState::GameState {
+                previous_inputs, ..
+            } => inputs.difference(previous_inputs).copied().collect(),
+            _ => InputSet::new(),
+        };
+
+        self.rewind(inputs.contains(&Action::Undo));
+
+        for action in &pressed {
+            match action {
+                Action::Ascend => self.climb(Direction::UP),
+                Action::Descend => self.climb(Direction::DOWN),
+                _ => {}
+            }
+        }
+
+        match self {
+            GameState::GameState { level_template, .. }
+                if level_template.movement == MovementMode::Grid =>
+            {
+                self.grid_move(&pressed)
+            }
+            _ => {
+                if !self.move_on_surface() {
+                    self.walk(inputs)
+                }
+            }
+        }
+
+        self.handle_input();
+
+        if let GameState::GameState {
+            previous_inputs,
+            old_position,
+            level_template,
+            level_state,
+            ..
+        } = self
+        {
+            level_state.update_buttons(&BTreeSet::from([*old_position]));
+            level_template.logic.update(level_state);
+            level_state.reveal_near(old_position);
+            previous_inputs.clone_from(inputs);
+        }
+
+        self.update_sharks();
+    }
+
+    // moves the player in the held directions at the level's speed,
+    // diagonal movement is as fast as straight movement
+    fn walk(&mut self, inputs: &InputSet) {
+        let GameState::GameState {
+            position,
+            level_template,
+            level_state,
+            ..
+        } = self
+        else {
+            return;
+        };
+
+        let directions: Vec<Direction> = inputs.iter().filter_map(Action::direction).collect();
+        let (mut dx, mut dy) = (0.0, 0.0);
+        for direction in &directions {
+            let (x, y) = direction.offset();
+            dx += x;
+            dy += y;
+        }
+        let length = f32::hypot(dx, dy);
+        if length == 0.0 {
+            return;
+        }
+        let step = level_template.speed * TICK_DURATION / length;
+        let blockers = collision::move_player(position, dx * step, dy * step, level_state);
+
+        let here = ObjectCoordinate::from(&*position);
+        for direction in directions {
+            if blockers.contains(&here.neighbor(direction)) {
+                self.bump(direction);
+            }
+        }
+    }
+
+    // walking into a rock pushes it, walking into a door with the right key unlocks it
+    // and walking into a secret may reveal it
+    pub(crate) fn bump(&mut self, direction: Direction) {
+        let GameState::GameState {
+            position,
+            level_state,
+            inventory,
+            history,
+            moves,
+            ..
+        } = self
+        else {
+            return;
+        };
+
+        let here = ObjectCoordinate::from(&*position);
+        let front = here.neighbor(direction);
+        if level_state.reveal_bumped(&front) {
+            return;
+        }
+        let push = level_state.can_push(&front, direction);
+        if !push && !level_state.can_unlock(&front, inventory) {
+            return;
+        }
+
+        history.record(Snapshot {
+            level_state: level_state.clone(),
+            inventory: inventory.clone(),
+            tile: here,
+            moves: moves.moves,
+        });
+        if push {
+            level_state.push_entity(&front, direction);
+        } else {
+            level_state.unlock(&front, inventory);
+        }
+    }
+
+    fn update_sharks(&mut self) {
+        if let GameState::GameState {
+            position,
+            level_template,
+            level_state,
+            ..
+        } = self
+        {
+            level_state.update_sharks();
+            if level_state
+                .sharks
+                .iter()
+                .any(|shark| shark.touches(position))
+            {
+                trace!("Eaten by a shark at {:?}", position);
+                *self = GameState::Lost {
+                    level_template: level_template.clone(),
+                };
+            }
+        }
+    }
+
+    fn climb(&mut self, direction: Direction) {
+        if let GameState::GameState {
+            position,
+            level_state,
+            grid,
+            ..
+        } = self
+        {
+            if grid.tween.is_some() {
+                return;
+            }
+            let here: ObjectCoordinate = (&*position).into();
+            if level_state.can_climb(&here, direction) {
+                trace!("Climbing {:?} from {:?}", direction, here);
+                position.z = here.neighbor(direction).z;
+            }
+        }
+    }
+
+    // moves the player from the teleporter they stand on to its exit
+    pub(crate) fn teleport(&mut self) {
+        let GameState::GameState {
+            position,
+            old_position,
+            level_state,
+            teleport_cooldown,
+            grid,
+            sliding,
+            ..
+        } = self
+        else {
+            return;
+        };
+        if *teleport_cooldown > 0 {
+            return;
+        }
+        let Some(exit) = level_state.teleport_exit(old_position) else {
+            error!("The teleporter at {:?} leads nowhere!", old_position);
+            return;
+        };
+
+        trace!("Teleporting from {:?} to {:?}", old_position, exit);
+        *position = PlayerCoordinate {
+            x: exit.x as f32,
+            y: exit.y as f32,
+            z: exit.z,
+        };
+        *old_position = exit;
+        *teleport_cooldown = TELEPORT_COOLDOWN;
+        **grid = GridMovement::default();
+        *sliding = None;
+
+        if let Some(fun) = level_state
+            .tile_map
+            .get_mut(&exit)
+            .and_then(TileType::step_on)
+        {
+            fun(self);
+        }
+    }
+
+    pub(crate) fn handle_input(&mut self) {
+        if let GameState::GameState {
+            position,
+            old_position,
+            level_state,
+            inventory,
+            history,
+            moves,
+            sliding,
+            ..
+        } = self
+        {
+            let new_pos: ObjectCoordinate = position.into();
+            if *old_position != new_pos {
+                history.record(Snapshot {
+                    level_state: level_state.clone(),
+                    inventory: inventory.clone(),
+                    tile: *old_position,
+                    moves: moves.moves,
+                });
+                moves.moves += 1;
+                // entering ice starts a slide in the direction of the move
+                *sliding = match level_state.tile_map.get(&new_pos) {
+                    Some(TileType::Ice) => sliding.or(old_position.direction_to(new_pos)),
+                    _ => None,
+                };
+                *old_position = new_pos;
+                trace! {"Stepping on {:?} with {:?}", old_position, position}
+                if let Some(fun) = level_state
+                    .tile_map
+                    .get_mut(old_position)
+                    .and_then(TileType::step_on)
+                {
+                    fun(self);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::game::simulation::TICK_DURATION;
+use crate::game::{collision, Direction, GameState, ObjectCoordinate, TileType};
+use log::trace;
+
+// how fast conveyors carry the player in free movement, in tiles per second
+pub const CONVEYOR_SPEED: f32 = 1.0;
+
+impl GameState {
+    // the direction the tile under the player moves them in on its own, if any
+    pub(crate) fn surface_direction(&self) -> Option<Direction> {
+        let GameState::GameState {
+            old_position,
+            level_state,
+            sliding,
+            ..
+        } = self
+        else {
+            return None;
+        };
+        match level_state.tile_map.get(old_position) {
+            Some(TileType::Ice) => *sliding,
+            Some(tile @ TileType::Conveyor { .. }) => tile.effective_facing(),
+            _ => None,
+        }
+    }
+
+    // slides the player over ice instead of walking and lets conveyors carry them,
+    // returns whether the player is sliding and can not walk
+    pub(crate) fn move_on_surface(&mut self) -> bool {
+        let Some(direction) = self.surface_direction() else {
+            return false;
+        };
+        let GameState::GameState {
+            position,
+            level_template,
+            level_state,
+            sliding,
+            ..
+        } = self
+        else {
+            return false;
+        };
+
+        let speed = match sliding {
+            Some(_) => level_template.speed,
+            None => CONVEYOR_SPEED,
+        };
+        let (dx, dy) = direction.offset();
+        let step = speed * TICK_DURATION;
+        let blockers = collision::move_player(position, dx * step, dy * step, level_state);
+
+        let front = ObjectCoordinate::from(&*position).neighbor(direction);
+        if sliding.is_some() && blockers.contains(&front) {
+            trace!("Stopped sliding in front of {:?}", front);
+            *sliding = None;
+        }
+        sliding.is_some()
+    }
+}
@@ -3,58 +3,72 @@ use std::collections::BTreeMap;
 
 pub fn test_level() -> LevelTemplate {
     use crate::game::level::*;
-    use EastWestAxis::*;
-    use NorthSouthAxis::*;
+    use crate::game::logic::*;
+    use crate::game::migration::LEVEL_VERSION;
+    use crate::game::progress::Par;
+    use crate::game::simulation::DEFAULT_SPEED;
+    use crate::game::MovementMode;
     use Orientation::*;
     use WallType::*;
 
     let mut tile_map = BTreeMap::new();
-    tile_map.insert(ObjectCoordinate { x: 0, y: 0 }, TileType::Start);
-    tile_map.insert(ObjectCoordinate { x: 0, y: 1 }, TileType::Path);
-    tile_map.insert(ObjectCoordinate { x: 1, y: 1 }, TileType::Path);
-    tile_map.insert(ObjectCoordinate { x: 2, y: 1 }, TileType::Path);
-    tile_map.insert(ObjectCoordinate { x: 2, y: 2 }, TileType::Path);
-    tile_map.insert(ObjectCoordinate { x: 2, y: 3 }, TileType::Path);
-    tile_map.insert(ObjectCoordinate { x: 1, y: 3 }, TileType::Path);
+    tile_map.insert(ObjectCoordinate { x: 0, y: 0, z: 0 }, TileType::Start);
+    tile_map.insert(ObjectCoordinate { x: 0, y: 1, z: 0 }, TileType::Path);
+    tile_map.insert(ObjectCoordinate { x: 1, y: 1, z: 0 }, TileType::Path);
+    tile_map.insert(ObjectCoordinate { x: 2, y: 1, z: 0 }, TileType::Path);
+    tile_map.insert(ObjectCoordinate { x: 2, y: 2, z: 0 }, TileType::Path);
+    tile_map.insert(ObjectCoordinate { x: 2, y: 3, z: 0 }, TileType::Path);
+    tile_map.insert(ObjectCoordinate { x: 1, y: 3, z: 0 }, TileType::Path);
     tile_map.insert(
-        ObjectCoordinate { x: 0, y: 2 },
+        ObjectCoordinate { x: 0, y: 2, z: 0 },
         TileType::Wall {
-            kind: Corner {
-                north_south_facing: North,
-                east_west_facing: West,
+            kind: Double {
+                orientation: Horizontal,
             },
         },
     );
     tile_map.insert(
-        ObjectCoordinate { x: 1, y: 2 },
+        ObjectCoordinate { x: 1, y: 2, z: 0 },
         TileType::Wall {
-            kind: Double {
-                orientation: Horizontal,
+            kind: End {
+                facing: Direction::EAST,
             },
         },
     );
     tile_map.insert(
-        ObjectCoordinate { x: -1, y: 2 },
+        ObjectCoordinate { x: -1, y: 2, z: 0 },
         TileType::Wall {
-            kind: Double {
-                orientation: Horizontal,
+            kind: End {
+                facing: Direction::WEST,
             },
         },
     );
     tile_map.insert(
-        ObjectCoordinate { x: 0, y: 3 },
+        ObjectCoordinate { x: 0, y: 3, z: 0 },
         TileType::Goal { active: false },
     );
     tile_map.insert(
-        ObjectCoordinate { x: -1, y: -1 },
+        ObjectCoordinate { x: -1, y: -1, z: 0 },
         TileType::Button {
             pressed: false,
             inverted: false,
-            target: ObjectCoordinate { x: 0, y: 3 },
+            targets: vec![ObjectCoordinate { x: 0, y: 3, z: 0 }],
+            kind: ButtonKind::Toggle,
         },
     );
     LevelTemplate {
+        version: LEVEL_VERSION,
         name: String::from("Test"),
-        init_state: LevelState { tile_map },
+        init_state: LevelState {
+            tile_map,
+            logic_state: LogicState::default(),
+            entities: BTreeMap::new(),
+            sharks: Vec::new(),
+        },
+        logic: LogicNetwork::default(),
+        order: 0,
+        movement: MovementMode::Free,
+        speed: DEFAULT_SPEED,
+        par: Par::default(),
     }
 }
@@ -0,0 +1,561 @@
+use crate::game::level::loading::{parse_level, LoadingError};
+use crate::game::level::saving::{to_ron, SavingError};
+use crate::game::{LevelState, LevelTemplate, ObjectCoordinate, TileType, TILE_SIZE};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+pub mod tmx;
+
+// the upper bits of a gid flip and rotate the tile, which levels do not support
+const GID_MASK: u32 = 0x1FFF_FFFF;
+
+#[derive(Debug)]
+pub enum TiledError {
+    Json(serde_json::Error),
+    Xml(roxmltree::Error),
+    Invalid(String),
+}
+
+impl Display for TiledError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            TiledError::Json(err) => Display::fmt(err, f),
+            TiledError::Xml(err) => Display::fmt(err, f),
+            TiledError::Invalid(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl From<serde_json::Error> for TiledError {
+    fn from(json_err: serde_json::Error) -> Self {
+        TiledError::Json(json_err)
+    }
+}
+
+impl From<roxmltree::Error> for TiledError {
+    fn from(xml_err: roxmltree::Error) -> Self {
+        TiledError::Xml(xml_err)
+    }
+}
+
+/// Which tile of the tileset stands for which [`TileType`].
+///
+/// Tile ids are local to the tileset, so the first tile has id 0.
+/// Button targets are not part of the mapping, they come from objects, see [`import`].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TiledMapping {
+    // the tileset exported maps refer to
+    pub tileset: String,
+    pub tiles: BTreeMap<u32, TileType>,
+}
+
+impl TiledMapping {
+    fn id_of(&self, tile: &TileType) -> Option<u32> {
+        self.tiles
+            .iter()
+            .find(|(_, mapped)| *mapped == tile)
+            .map(|(id, _)| *id)
+    }
+}
+
+// the parts of a Tiled map that levels use, as written to .tmj files
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TiledMap {
+    #[serde(default = "TiledMap::default_orientation")]
+    pub orientation: String,
+    pub width: u32,
+    pub height: u32,
+    #[serde(rename = "tilewidth")]
+    pub tile_width: u32,
+    #[serde(rename = "tileheight")]
+    pub tile_height: u32,
+    #[serde(default)]
+    pub infinite: bool,
+    #[serde(default, rename = "nextlayerid")]
+    pub next_layer_id: u32,
+    #[serde(default, rename = "nextobjectid")]
+    pub next_object_id: u32,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+    #[serde(default)]
+    pub tilesets: Vec<TilesetRef>,
+    pub layers: Vec<Layer>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TilesetRef {
+    #[serde(rename = "firstgid")]
+    pub first_gid: u32,
+    // empty for tilesets embedded into the map
+    #[serde(default)]
+    pub source: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Layer {
+    #[serde(rename = "tilelayer")]
+    Tiles(TileLayer),
+    #[serde(rename = "objectgroup")]
+    Objects(ObjectLayer),
+    // image layers and groups are ignored
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TileLayer {
+    pub id: u32,
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    // row by row, 0 is an empty cell
+    pub data: Vec<u32>,
+    #[serde(default = "TiledMap::default_visible")]
+    pub visible: bool,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ObjectLayer {
+    pub id: u32,
+    pub name: String,
+    pub objects: Vec<Object>,
+    #[serde(default = "TiledMap::default_visible")]
+    pub visible: bool,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Object {
+    pub id: u32,
+    #[serde(default)]
+    pub name: String,
+    // called class since Tiled 1.9
+    #[serde(default, rename = "type", alias = "class")]
+    pub class: String,
+    // in pixels, from the top left corner of the map
+    pub x: f32,
+    pub y: f32,
+    #[serde(default)]
+    pub width: f32,
+    #[serde(default)]
+    pub height: f32,
+    #[serde(default = "TiledMap::default_visible")]
+    pub visible: bool,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Property {
+    pub name: String,
+    #[serde(rename = "type", default = "Property::default_type")]
+    pub kind: String,
+    pub value: serde_json::Value,
+}
+
+impl Property {
+    fn default_type() -> String {
+        "string".to_string()
+    }
+
+    fn int(name: &str, value: i64) -> Self {
+        Property {
+            name: name.to_string(),
+            kind: "int".to_string(),
+            value: value.into(),
+        }
+    }
+
+    fn string(name: &str, value: String) -> Self {
+        Property {
+            name: name.to_string(),
+            kind: Property::default_type(),
+            value: value.into(),
+        }
+    }
+
+    // a reference to another object by its id
+    fn object(name: &str, id: u32) -> Self {
+        Property {
+            name: name.to_string(),
+            kind: "object".to_string(),
+            value: id.into(),
+        }
+    }
+}
+
+fn find_property<'a>(properties: &'a [Property], name: &str) -> Option<&'a serde_json::Value> {
+    properties
+        .iter()
+        .find(|property| property.name == name)
+        .map(|property| &property.value)
+}
+
+fn find_int(properties: &[Property], name: &str) -> Option<i64> {
+    find_property(properties, name).and_then(serde_json::Value::as_i64)
+}
+
+impl TiledMap {
+    fn default_orientation() -> String {
+        "orthogonal".to_string()
+    }
+
+    fn default_visible() -> bool {
+        true
+    }
+
+    pub fn from_tmj(content: &str) -> Result<TiledMap, TiledError> {
+        Ok(serde_json::from_str(content)?)
+    }
+
+    pub fn to_tmj(&self) -> Result<String, TiledError> {
+        // Tiled expects a few fields levels do not care about
+        let mut json = serde_json::to_value(self)?;
+        if let serde_json::Value::Object(map) = &mut json {
+            map.insert("type".to_string(), "map".into());
+            map.insert("version".to_string(), "1.10".into());
+            map.insert("renderorder".to_string(), "right-down".into());
+        }
+        Ok(serde_json::to_string_pretty(&json)?)
+    }
+}
+
+// objects are placed on the tile below their top left corner
+fn object_coordinate(
+    map: &TiledMap,
+    object: &Object,
+    origin: (i64, i64),
+    z: i64,
+) -> ObjectCoordinate {
+    ObjectCoordinate {
+        x: origin.0 + (object.x / map.tile_width as f32).floor() as i64,
+        y: origin.1 + (object.y / map.tile_height as f32).floor() as i64,
+        z,
+    }
+}
+
+// the gids of the tileset the mapping is for, tilesets are told apart by their file name
+fn tileset_gids(map: &TiledMap, tileset: &str) -> Option<std::ops::Range<u32>> {
+    let file_name = Path::new(tileset).file_name();
+    let first_gid = map
+        .tilesets
+        .iter()
+        .find(|used| Path::new(&used.source).file_name() == file_name)?
+        .first_gid;
+    // the next tileset starts where this one ends
+    let end = map
+        .tilesets
+        .iter()
+        .map(|used| used.first_gid)
+        .filter(|gid| *gid > first_gid)
+        .min()
+        .unwrap_or(u32::MAX);
+    Some(first_gid..end)
+}
+
+/// Reads a level from a Tiled map.
+///
+/// Every tile layer is a floor, given by its `floor` property or its position among the tile layers,
+/// object layers without a `floor` property belong to the floor of the tile layer before them.
+/// Only tiles of the tileset named by the mapping are understood.
+/// The map property `level` may hold the rest of the level in RON, its tile map holds the tiles
+/// the mapping has no tile for. Objects of class `target` add their tile to the targets
+/// of the `button` object their `button` property refers to.
+/// The map properties `origin_x` and `origin_y` give the coordinate of the top left tile.
+pub fn import(map: &TiledMap, mapping: &TiledMapping) -> Result<LevelTemplate, LoadingError> {
+    let invalid = |message: String| LoadingError::Tiled(TiledError::Invalid(message));
+    if map.infinite {
+        return Err(invalid("infinite maps are not supported".to_string()));
+    }
+
+    let mut level = match find_property(&map.properties, "level") {
+        Some(serde_json::Value::String(content)) => parse_level(content)?,
+        Some(_) => return Err(invalid("the level property is not a string".to_string())),
+        None => LevelTemplate::new(
+            find_property(&map.properties, "name")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or("Untitled"),
+            LevelState::default(),
+        ),
+    };
+    let unmapped = std::mem::take(&mut level.init_state.tile_map);
+    let origin = (
+        find_int(&map.properties, "origin_x").unwrap_or_default(),
+        find_int(&map.properties, "origin_y").unwrap_or_default(),
+    );
+    let gids = tileset_gids(map, &mapping.tileset);
+
+    let mut buttons = BTreeMap::new();
+    let mut targets = vec![];
+    let mut tile_layers = 0;
+    // object layers without a floor property are on the floor of the tile layer before them
+    let mut floor = 0;
+    for layer in &map.layers {
+        match layer {
+            Layer::Tiles(layer) => {
+                let z = find_int(&layer.properties, "floor").unwrap_or(tile_layers);
+                tile_layers += 1;
+                floor = z;
+                if layer.data.len() != (layer.width * layer.height) as usize {
+                    return Err(invalid(format!(
+                        "layer {} has {} tiles instead of {}",
+                        layer.name,
+                        layer.data.len(),
+                        layer.width * layer.height
+                    )));
+                }
+                for (index, gid) in layer.data.iter().enumerate() {
+                    let gid = gid & GID_MASK;
+                    if gid == 0 {
+                        continue;
+                    }
+                    let tile = gids
+                        .as_ref()
+                        .filter(|gids| gids.contains(&gid))
+                        .and_then(|gids| mapping.tiles.get(&(gid - gids.start)))
+                        .ok_or_else(|| {
+                            invalid(format!(
+                                "tile {} in layer {} has no mapping",
+                                gid, layer.name
+                            ))
+                        })?;
+                    let coord = ObjectCoordinate {
+                        x: origin.0 + (index % layer.width as usize) as i64,
+                        y: origin.1 + (index / layer.width as usize) as i64,
+                        z,
+                    };
+                    level.init_state.tile_map.insert(coord, tile.clone());
+                }
+            }
+            Layer::Objects(layer) => {
+                let z = find_int(&layer.properties, "floor").unwrap_or(floor);
+                for object in &layer.objects {
+                    let coord = object_coordinate(map, object, origin, z);
+                    match object.class.as_str() {
+                        "button" => {
+                            buttons.insert(object.id, coord);
+                        }
+                        "target" => targets.push((object.id, object, coord)),
+                        _ => {}
+                    }
+                }
+            }
+            Layer::Other => {}
+        }
+    }
+
+    // in the order the targets were placed in
+    targets.sort_by_key(|(id, _, _)| *id);
+    for (id, object, target) in targets {
+        let button = find_int(&object.properties, "button")
+            .and_then(|button| buttons.get(&(button as u32)))
+            .ok_or_else(|| invalid(format!("target {id} does not refer to a button object")))?;
+        match level.init_state.tile_map.get_mut(button) {
+            Some(TileType::Button { targets, .. }) => targets.push(target),
+            _ => {
+                return Err(invalid(format!(
+                    "the button of target {id} is not on a button"
+                )))
+            }
+        }
+    }
+
+    level.init_state.tile_map.extend(unmapped);
+    Ok(level)
+}
+
+/// Writes a level as a Tiled map, see [`import`].
+pub fn export(level: &LevelTemplate, mapping: &TiledMapping) -> Result<TiledMap, ron::Error> {
+    let tile_map = &level.init_state.tile_map;
+    let mut floors: BTreeSet<i64> = tile_map.keys().map(|coord| coord.z).collect();
+    let min_x = tile_map
+        .keys()
+        .map(|coord| coord.x)
+        .min()
+        .unwrap_or_default();
+    let min_y = tile_map
+        .keys()
+        .map(|coord| coord.y)
+        .min()
+        .unwrap_or_default();
+    let width = tile_map
+        .keys()
+        .map(|coord| coord.x - min_x + 1)
+        .max()
+        .unwrap_or_default() as u32;
+    let height = tile_map
+        .keys()
+        .map(|coord| coord.y - min_y + 1)
+        .max()
+        .unwrap_or_default() as u32;
+    let first_gid = 1;
+
+    let mut data: BTreeMap<i64, Vec<u32>> = floors
+        .iter()
+        .map(|z| (*z, vec![0; (width * height) as usize]))
+        .collect();
+    let mut objects: BTreeMap<i64, Vec<Object>> = BTreeMap::new();
+    let mut unmapped = BTreeMap::new();
+    let mut next_object_id = 1;
+    let mut object = |coord: &ObjectCoordinate, class: &str, properties: Vec<Property>| {
+        let id = next_object_id;
+        next_object_id += 1;
+        objects.entry(coord.z).or_default().push(Object {
+            id,
+            name: String::new(),
+            class: class.to_string(),
+            x: (coord.x - min_x) as f32 * TILE_SIZE,
+            y: (coord.y - min_y) as f32 * TILE_SIZE,
+            width: TILE_SIZE,
+            height: TILE_SIZE,
+            visible: true,
+            properties,
+        });
+        id
+    };
+
+    for (coord, tile) in tile_map {
+        // the targets of mapped buttons are written as objects
+        let mut mapped = tile.clone();
+        let mut button_targets = vec![];
+        if let TileType::Button { targets, .. } = &mut mapped {
+            button_targets = std::mem::take(targets);
+        }
+        let Some(id) = mapping.id_of(&mapped) else {
+            unmapped.insert(*coord, tile.clone());
+            continue;
+        };
+
+        let index = (coord.y - min_y) as usize * width as usize + (coord.x - min_x) as usize;
+        if let Some(layer) = data.get_mut(&coord.z) {
+            layer[index] = first_gid + id;
+        }
+        if !button_targets.is_empty() {
+            let button = object(coord, "button", vec![]);
+            for target in &button_targets {
+                object(target, "target", vec![Property::object("button", button)]);
+            }
+        }
+    }
+
+    // targets may be on floors without tiles, those get an empty tile layer
+    floors.extend(objects.keys());
+
+    let properties = LevelTemplate {
+        init_state: LevelState {
+            tile_map: unmapped,
+            ..level.init_state.clone()
+        },
+        ..level.clone()
+    };
+
+    let mut layers = vec![];
+    let mut next_layer_id = 1;
+    for z in floors {
+        layers.push(Layer::Tiles(TileLayer {
+            id: next_layer_id,
+            name: format!("Floor {z}"),
+            width,
+            height,
+            data: data
+                .remove(&z)
+                .unwrap_or_else(|| vec![0; (width * height) as usize]),
+            visible: true,
+            properties: vec![Property::int("floor", z)],
+        }));
+        next_layer_id += 1;
+        if let Some(objects) = objects.remove(&z) {
+            layers.push(Layer::Objects(ObjectLayer {
+                id: next_layer_id,
+                name: format!("Buttons {z}"),
+                objects,
+                visible: true,
+                properties: vec![Property::int("floor", z)],
+            }));
+            next_layer_id += 1;
+        }
+    }
+
+    Ok(TiledMap {
+        orientation: TiledMap::default_orientation(),
+        width,
+        height,
+        tile_width: TILE_SIZE as u32,
+        tile_height: TILE_SIZE as u32,
+        infinite: false,
+        next_layer_id,
+        next_object_id,
+        properties: vec![
+            Property::string("level", to_ron(&properties)?),
+            Property::int("origin_x", min_x),
+            Property::int("origin_y", min_y),
+        ],
+        tilesets: vec![TilesetRef {
+            first_gid,
+            source: mapping.tileset.clone(),
+        }],
+        layers,
+    })
+}
+
+pub fn is_tiled_level(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "tmj" || ext == "tmx")
+}
+
+// the mapping used for all Tiled maps in the levels folder
+pub fn mapping_path(asset_path: &Path) -> PathBuf {
+    asset_path.join("tiled").join("mapping.ron")
+}
+
+pub fn load_mapping(path: &Path) -> Result<TiledMapping, LoadingError> {
+    let mut content = vec![];
+    File::open(path)?.read_to_end(&mut content)?;
+    Ok(ron::de::from_bytes(&content)?)
+}
+
+// reads .tmx files as XML and everything else as JSON
+pub fn load_tiled(path: &Path, mapping: &TiledMapping) -> Result<LevelTemplate, LoadingError> {
+    let mut content = String::new();
+    File::open(path)?.read_to_string(&mut content)?;
+
+    let map = if path.extension().is_some_and(|ext| ext == "tmx") {
+        tmx::from_tmx(&content)
+    } else {
+        TiledMap::from_tmj(&content)
+    }
+    .map_err(LoadingError::Tiled)?;
+    import(&map, mapping)
+}
+
+pub fn save_tiled(
+    path: &Path,
+    level: &LevelTemplate,
+    mapping: &TiledMapping,
+) -> Result<(), SavingError> {
+    let map = export(level, mapping)?;
+    let out = if path.extension().is_some_and(|ext| ext == "tmx") {
+        tmx::to_tmx(&map)
+    } else {
+        map.to_tmj().map_err(SavingError::Tiled)?
+    };
+
+    if let Some(parent) = path.parent() {
+        if !parent.exists() {
+            std::fs::create_dir_all(parent)?
+        }
+    }
+
+    info!("Exporting level {} to {:?}.", level.name, path);
+    File::create(path)?.write_all(out.as_bytes())?;
+    Ok(())
+}
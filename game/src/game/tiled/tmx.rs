@@ -0,0 +1,292 @@
+use crate::game::tiled::{
+    Layer, Object, ObjectLayer, Property, TileLayer, TiledError, TiledMap, TilesetRef,
+};
+use roxmltree::Node;
+use std::fmt::Write;
+
+fn invalid(message: String) -> TiledError {
+    TiledError::Invalid(message)
+}
+
+fn attribute<T: std::str::FromStr>(node: &Node, name: &str) -> Result<T, TiledError> {
+    node.attribute(name)
+        .ok_or_else(|| invalid(format!("<{}> is missing {}", node.tag_name().name(), name)))?
+        .parse()
+        .map_err(|_| {
+            invalid(format!(
+                "<{}> has an invalid {}",
+                node.tag_name().name(),
+                name
+            ))
+        })
+}
+
+fn optional_attribute<T: std::str::FromStr>(
+    node: &Node,
+    name: &str,
+    default: T,
+) -> Result<T, TiledError> {
+    match node.attribute(name) {
+        Some(_) => attribute(node, name),
+        None => Ok(default),
+    }
+}
+
+fn children<'a, 'input>(
+    node: &Node<'a, 'input>,
+    tag: &'static str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(move |child| child.has_tag_name(tag))
+}
+
+// multi-line string properties keep their value in the element text
+fn properties(node: &Node) -> Result<Vec<Property>, TiledError> {
+    let mut properties = vec![];
+    for list in children(node, "properties") {
+        for property in children(&list, "property") {
+            let kind = property.attribute("type").unwrap_or("string").to_string();
+            let text = property
+                .attribute("value")
+                .or_else(|| property.text())
+                .unwrap_or_default();
+            let value = match kind.as_str() {
+                "int" | "object" => text
+                    .parse::<i64>()
+                    .map_err(|_| invalid(format!("{text} is not an integer")))?
+                    .into(),
+                "float" => text
+                    .parse::<f64>()
+                    .map_err(|_| invalid(format!("{text} is not a number")))?
+                    .into(),
+                "bool" => (text == "true").into(),
+                _ => text.into(),
+            };
+            properties.push(Property {
+                name: attribute(&property, "name")?,
+                kind,
+                value,
+            });
+        }
+    }
+    Ok(properties)
+}
+
+fn tile_layer(node: &Node) -> Result<TileLayer, TiledError> {
+    let data = children(node, "data")
+        .next()
+        .ok_or_else(|| invalid("a <layer> has no <data>".to_string()))?;
+    let gids = match data.attribute("encoding") {
+        Some("csv") => data
+            .text()
+            .unwrap_or_default()
+            .split(',')
+            .map(|gid| gid.trim().parse())
+            .collect::<Result<Vec<u32>, _>>()
+            .map_err(|_| invalid("the layer data is not a list of tiles".to_string()))?,
+        None => children(&data, "tile")
+            .map(|tile| optional_attribute(&tile, "gid", 0))
+            .collect::<Result<_, _>>()?,
+        Some(encoding) => {
+            return Err(invalid(format!(
+                "{encoding} encoded layers are not supported"
+            )))
+        }
+    };
+
+    Ok(TileLayer {
+        id: attribute(node, "id")?,
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        width: attribute(node, "width")?,
+        height: attribute(node, "height")?,
+        data: gids,
+        visible: optional_attribute::<u8>(node, "visible", 1)? != 0,
+        properties: properties(node)?,
+    })
+}
+
+fn object_layer(node: &Node) -> Result<ObjectLayer, TiledError> {
+    let mut objects = vec![];
+    for object in children(node, "object") {
+        objects.push(Object {
+            id: attribute(&object, "id")?,
+            name: object.attribute("name").unwrap_or_default().to_string(),
+            class: object
+                .attribute("class")
+                .or_else(|| object.attribute("type"))
+                .unwrap_or_default()
+                .to_string(),
+            x: attribute(&object, "x")?,
+            y: attribute(&object, "y")?,
+            width: optional_attribute(&object, "width", 0.0)?,
+            height: optional_attribute(&object, "height", 0.0)?,
+            visible: optional_attribute::<u8>(&object, "visible", 1)? != 0,
+            properties: properties(&object)?,
+        });
+    }
+
+    Ok(ObjectLayer {
+        id: attribute(node, "id")?,
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        objects,
+        visible: optional_attribute::<u8>(node, "visible", 1)? != 0,
+        properties: properties(node)?,
+    })
+}
+
+pub fn from_tmx(content: &str) -> Result<TiledMap, TiledError> {
+    let document = roxmltree::Document::parse(content)?;
+    let map = document.root_element();
+    if !map.has_tag_name("map") {
+        return Err(invalid("the file does not contain a <map>".to_string()));
+    }
+
+    let mut tilesets = vec![];
+    for tileset in children(&map, "tileset") {
+        tilesets.push(TilesetRef {
+            first_gid: attribute(&tileset, "firstgid")?,
+            source: tileset.attribute("source").unwrap_or_default().to_string(),
+        });
+    }
+
+    let mut layers = vec![];
+    for node in map.children() {
+        match node.tag_name().name() {
+            "layer" => layers.push(Layer::Tiles(tile_layer(&node)?)),
+            "objectgroup" => layers.push(Layer::Objects(object_layer(&node)?)),
+            _ => {}
+        }
+    }
+
+    Ok(TiledMap {
+        orientation: optional_attribute(&map, "orientation", TiledMap::default_orientation())?,
+        width: attribute(&map, "width")?,
+        height: attribute(&map, "height")?,
+        tile_width: attribute(&map, "tilewidth")?,
+        tile_height: attribute(&map, "tileheight")?,
+        infinite: optional_attribute::<u8>(&map, "infinite", 0)? != 0,
+        next_layer_id: optional_attribute(&map, "nextlayerid", 0)?,
+        next_object_id: optional_attribute(&map, "nextobjectid", 0)?,
+        properties: properties(&map)?,
+        tilesets,
+        layers,
+    })
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_properties(out: &mut String, indent: &str, properties: &[Property]) {
+    if properties.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "{indent}<properties>");
+    for property in properties {
+        let value = match &property.value {
+            serde_json::Value::String(text) => text.clone(),
+            value => value.to_string(),
+        };
+        if value.contains('\n') {
+            let _ = writeln!(
+                out,
+                "{indent} <property name=\"{}\">{}</property>",
+                escape(&property.name),
+                escape(&value)
+            );
+        } else {
+            let _ = writeln!(
+                out,
+                "{indent} <property name=\"{}\" type=\"{}\" value=\"{}\"/>",
+                escape(&property.name),
+                escape(&property.kind),
+                escape(&value)
+            );
+        }
+    }
+    let _ = writeln!(out, "{indent}</properties>");
+}
+
+pub fn to_tmx(map: &TiledMap) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        out,
+        r#"<map version="1.10" orientation="{}" renderorder="right-down" width="{}" height="{}" tilewidth="{}" tileheight="{}" infinite="{}" nextlayerid="{}" nextobjectid="{}">"#,
+        escape(&map.orientation),
+        map.width,
+        map.height,
+        map.tile_width,
+        map.tile_height,
+        map.infinite as u8,
+        map.next_layer_id,
+        map.next_object_id
+    );
+    write_properties(&mut out, " ", &map.properties);
+    for tileset in &map.tilesets {
+        let _ = writeln!(
+            out,
+            r#" <tileset firstgid="{}" source="{}"/>"#,
+            tileset.first_gid,
+            escape(&tileset.source)
+        );
+    }
+
+    for layer in &map.layers {
+        match layer {
+            Layer::Tiles(layer) => {
+                let _ = writeln!(
+                    out,
+                    r#" <layer id="{}" name="{}" width="{}" height="{}" visible="{}">"#,
+                    layer.id,
+                    escape(&layer.name),
+                    layer.width,
+                    layer.height,
+                    layer.visible as u8
+                );
+                write_properties(&mut out, "  ", &layer.properties);
+                let rows: Vec<String> = layer
+                    .data
+                    .chunks(layer.width.max(1) as usize)
+                    .map(|row| row.iter().map(u32::to_string).collect::<Vec<_>>().join(","))
+                    .collect();
+                let _ = writeln!(out, r#"  <data encoding="csv">"#);
+                let _ = writeln!(out, "{}", rows.join(",\n"));
+                let _ = writeln!(out, "</data>");
+                let _ = writeln!(out, " </layer>");
+            }
+            Layer::Objects(layer) => {
+                let _ = writeln!(
+                    out,
+                    r#" <objectgroup id="{}" name="{}" visible="{}">"#,
+                    layer.id,
+                    escape(&layer.name),
+                    layer.visible as u8
+                );
+                write_properties(&mut out, "  ", &layer.properties);
+                for object in &layer.objects {
+                    let _ = writeln!(
+                        out,
+                        r#"  <object id="{}" name="{}" type="{}" x="{}" y="{}" width="{}" height="{}" visible="{}">"#,
+                        object.id,
+                        escape(&object.name),
+                        escape(&object.class),
+                        object.x,
+                        object.y,
+                        object.width,
+                        object.height,
+                        object.visible as u8
+                    );
+                    write_properties(&mut out, "   ", &object.properties);
+                    let _ = writeln!(out, "  </object>");
+                }
+                let _ = writeln!(out, " </objectgroup>");
+            }
+            Layer::Other => {}
+        }
+    }
+    let _ = writeln!(out, "</map>");
+    out
+}
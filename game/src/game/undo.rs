@@ -0,0 +1,106 @@
+use crate::game::grid::GridMovement;
+use crate::game::inventory::Inventory;
+use crate::game::simulation::TICK_RATE;
+use crate::game::{GameState, LevelState, ObjectCoordinate, PlayerCoordinate};
+use log::trace;
+use std::collections::VecDeque;
+
+// the oldest snapshots are dropped once there are more than this many
+pub const UNDO_LIMIT: usize = 1000;
+
+// holding undo starts rewinding after REWIND_DELAY ticks
+// and then undoes another move every REWIND_INTERVAL ticks
+pub const REWIND_DELAY: u32 = TICK_RATE / 2;
+pub const REWIND_INTERVAL: u32 = TICK_RATE / 10;
+
+// the state right before a move, restoring it puts the player back onto `tile`
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    pub level_state: Box<LevelState>,
+    pub inventory: Inventory,
+    pub tile: ObjectCoordinate,
+    pub moves: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    pub snapshots: VecDeque<Snapshot>,
+    // for how many ticks undo has been held down
+    pub held_for: u32,
+}
+
+impl History {
+    // only the first snapshot of a move is kept, so pushing a rock
+    // and then stepping onto its tile is undone in one go
+    pub fn record(&mut self, snapshot: Snapshot) {
+        if self
+            .snapshots
+            .back()
+            .is_some_and(|last| last.moves == snapshot.moves && last.tile == snapshot.tile)
+        {
+            return;
+        }
+        if self.snapshots.len() >= UNDO_LIMIT {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+}
+
+impl GameState {
+    /// Reverts the last move, returns whether there was a move to revert.
+    pub fn undo(&mut self) -> bool {
+        let GameState::GameState {
+            position,
+            old_position,
+            level_state,
+            inventory,
+            history,
+            moves,
+            grid,
+            sliding,
+            ..
+        } = self
+        else {
+            return false;
+        };
+        let Some(snapshot) = history.snapshots.pop_back() else {
+            return false;
+        };
+
+        trace!("Undoing the move from {:?}", snapshot.tile);
+        *level_state = snapshot.level_state;
+        *inventory = snapshot.inventory;
+        *position = PlayerCoordinate {
+            x: snapshot.tile.x as f32,
+            y: snapshot.tile.y as f32,
+            z: snapshot.tile.z,
+        };
+        *old_position = snapshot.tile;
+        **grid = GridMovement::default();
+        *sliding = None;
+        moves.moves = snapshot.moves;
+        moves.undos += 1;
+        true
+    }
+
+    // pressing undo reverts a single move, holding it rewinds continuously
+    pub(crate) fn rewind(&mut self, held: bool) {
+        let GameState::GameState { history, .. } = self else {
+            return;
+        };
+        if !held {
+            history.held_for = 0;
+            return;
+        }
+
+        let held_for = history.held_for;
+        history.held_for += 1;
+        if held_for == 0
+            || (held_for >= REWIND_DELAY
+                && (held_for - REWIND_DELAY).is_multiple_of(REWIND_INTERVAL))
+        {
+            self.undo();
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::game::{GateVisibility, LevelTemplate, ObjectCoordinate, TileType};
+use std::fmt::{Display, Formatter};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    // the level works, but probably not as intended
+    Warning,
+    // the level can not be played or misbehaves
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    // the tile the problem is found at, `None` for problems of the whole level
+    pub coordinate: Option<ObjectCoordinate>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(coordinate: Option<ObjectCoordinate>, message: String) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            coordinate,
+            message,
+        }
+    }
+
+    fn warning(coordinate: Option<ObjectCoordinate>, message: String) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            coordinate,
+            message,
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        match self.coordinate {
+            Some(ObjectCoordinate { x, y, z }) => {
+                write!(f, "{severity} at ({x}, {y}, {z}): {}", self.message)
+            }
+            None => write!(f, "{severity}: {}", self.message),
+        }
+    }
+}
+
+/// Checks a level for mistakes that the game would only notice while playing it, if at all.
+///
+/// Diagnostics are ordered by coordinate, problems of the whole level come first.
+pub fn validate(level: &LevelTemplate) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    check_start_and_goal(level, &mut diagnostics);
+    check_targets(level, &mut diagnostics);
+    check_hidden_gates(level, &mut diagnostics);
+    check_walls(level, &mut diagnostics);
+    diagnostics.sort_by_key(|diagnostic| diagnostic.coordinate);
+    diagnostics
+}
+
+fn check_start_and_goal(level: &LevelTemplate, diagnostics: &mut Vec<Diagnostic>) {
+    let starts = level.init_state.spawn_points();
+    match starts.as_slice() {
+        [] => diagnostics.push(Diagnostic::error(
+            None,
+            "there is no Start tile".to_string(),
+        )),
+        [_] => {}
+        [_, extra @ ..] => {
+            for start in extra {
+                diagnostics.push(Diagnostic::error(
+                    Some(*start),
+                    format!("one of {} Start tiles, only one is allowed", starts.len()),
+                ));
+            }
+        }
+    }
+
+    let has_goal = level
+        .init_state
+        .tile_map
+        .values()
+        .any(|tile| matches!(tile, TileType::Goal { .. }));
+    if !has_goal {
+        diagnostics.push(Diagnostic::error(None, "there is no Goal tile".to_string()));
+    }
+}
+
+// buttons and logic network sinks have to control tiles that accept a signal
+fn check_targets(level: &LevelTemplate, diagnostics: &mut Vec<Diagnostic>) {
+    let tile_map = &level.init_state.tile_map;
+    let describe = |target: &ObjectCoordinate| match tile_map.get(target) {
+        Some(tile) if tile.accepts_signal() => None,
+        Some(tile) => Some(format!("{:?}", tile.tile_texture_id())),
+        None => Some("nothing".to_string()),
+    };
+
+    for (coord, tile) in tile_map {
+        if let TileType::Button { targets, .. } = tile {
+            for target in targets {
+                if let Some(found) = describe(target) {
+                    diagnostics.push(Diagnostic::error(
+                        Some(*coord),
+                        format!("the button targets {target:?}, but there is {found} to control"),
+                    ));
+                }
+            }
+        }
+    }
+
+    for sink in level.logic.sinks.keys() {
+        if let Some(found) = describe(sink) {
+            diagnostics.push(Diagnostic::error(
+                Some(*sink),
+                format!("the logic network drives {found}, which does not accept a signal"),
+            ));
+        }
+    }
+}
+
+// a gate mimicking a gate shows gate textures while hidden, giving the secret away
+fn check_hidden_gates(level: &LevelTemplate, diagnostics: &mut Vec<Diagnostic>) {
+    for (coord, tile) in &level.init_state.tile_map {
+        if let TileType::Gate {
+            hidden: GateVisibility::Hidden(mimic),
+            ..
+        } = tile
+        {
+            if matches!(**mimic, TileType::Gate { .. }) {
+                diagnostics.push(Diagnostic::warning(
+                    Some(*coord),
+                    "the hidden gate mimics another gate".to_string(),
+                ));
+            }
+        }
+    }
+}
+
+fn check_walls(level: &LevelTemplate, diagnostics: &mut Vec<Diagnostic>) {
+    for (coord, tile) in &level.init_state.tile_map {
+        if let TileType::Wall { kind } | TileType::FakeWall { kind, .. } = tile {
+            let expected = level.init_state.expected_wall_type(coord);
+            if *kind != expected {
+                diagnostics.push(Diagnostic::warning(
+                    Some(*coord),
+                    format!("the wall is a {kind:?}, but its neighbours make it a {expected:?}"),
+                ));
+            }
+        }
+    }
+}
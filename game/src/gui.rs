@@ -1,8 +1,14 @@
 use crate::{
-    app::{Action, UpdateAction},
+    app::UpdateAction,
     game::{
+        camera::{Bounds, Camera},
         color::{IN_GAME_BACKGROUND, MENU_BACKGROUND},
-        GameState, LevelTemplate, TileTextureIndex,
+        level::loading::{self, load_levels, BrokenFile},
+        progress::{self, Progress, RunResult},
+        replay::{self, Replay},
+        simulation::TICK_RATE,
+        validation::{self, Severity},
+        Action, GameState, InputSet, LevelTemplate, TileTextureIndex,
     },
     gui::MenuState::InGame,
 };
@@ -12,6 +18,7 @@ use nannou_egui::{
     Egui, FrameCtx,
 };
 
+use log::{error, warn};
 use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::rc::Rc;
@@ -20,12 +27,27 @@ use learning_conrod_core::{get_asset_path, gui::TextureMap};
 
 #[derive(Debug)]
 pub enum MenuState {
-    InGame { state: GameState, paused: bool },
+    InGame {
+        state: Box<GameState>,
+        paused: bool,
+        // the inputs of the current attempt, for saving a replay
+        replay: Replay,
+        camera: Camera,
+        // the levels of the level selection, in level order
+        levels: Vec<Rc<LevelTemplate>>,
+    },
     LevelSelect(LevelSelectState),
 }
 
 #[derive(Debug)]
-pub struct LevelSelectState(Vec<Rc<LevelTemplate>>);
+pub struct LevelSelectState {
+    levels: Vec<Rc<LevelTemplate>>,
+    // level files that failed to load, shown so their authors can fix them
+    broken: Vec<BrokenFile>,
+    // why the last selected level could not be started
+    error: Option<String>,
+    progress: Progress,
+}
 
 pub trait Menu: Debug {
     fn handle_esc(&mut self, window: WindowId) -> UpdateAction;
@@ -33,27 +55,85 @@ pub trait Menu: Debug {
 
 impl MenuState {
     pub(crate) fn open_level_selection() -> Self {
-        let levels = crate::game::level::loading::load_levels(get_asset_path().as_path())
-            .unwrap_or_else(|_err| Vec::new())
-            .into_iter()
-            .map(Rc::new)
-            .collect();
+        let (levels, broken, error) = match load_levels(get_asset_path().as_path()) {
+            Ok(directory) => (
+                directory.loaded.into_iter().map(Rc::new).collect(),
+                directory.broken,
+                None,
+            ),
+            Err(err) => {
+                error!("Failed to load the levels: {}", err);
+                (
+                    Vec::new(),
+                    Vec::new(),
+                    Some(format!("Failed to load the levels: {err}")),
+                )
+            }
+        };
+
+        let progress = progress::load_progress(&progress::progress_path(&get_asset_path()))
+            .unwrap_or_else(|_err| {
+                error!("Failed to load the save file!");
+                Progress::default()
+            });
+
+        MenuState::LevelSelect(LevelSelectState {
+            levels,
+            broken,
+            error,
+            progress,
+        })
+    }
 
-        MenuState::LevelSelect(LevelSelectState(levels))
+    fn record_win(level: &LevelTemplate, result: &RunResult) {
+        progress::record_win(&progress::progress_path(&get_asset_path()), level, result);
+    }
+
+    pub(crate) fn start_level(level: Rc<LevelTemplate>, levels: Vec<Rc<LevelTemplate>>) -> Self {
+        for diagnostic in validation::validate(&level) {
+            match diagnostic.severity {
+                Severity::Warning => warn!("Level {}: {}", level.name, diagnostic),
+                Severity::Error => error!("Level {}: {}", level.name, diagnostic),
+            }
+        }
+
+        let started = GameState::new(level.clone())
+            .map_err(|err| err.to_string())
+            .and_then(|state| Ok((state, Replay::new(&level).map_err(|err| err.to_string())?)));
+        match started {
+            Ok((state, replay)) => {
+                let mut camera = Camera::new(Bounds::of_level(&level.init_state));
+                if let GameState::GameState { position, .. } = &state {
+                    camera.jump_to(position);
+                }
+                MenuState::InGame {
+                    replay,
+                    state: Box::new(state),
+                    paused: false,
+                    camera,
+                    levels,
+                }
+            }
+            Err(err) => {
+                error!("{}", err);
+                let mut menu = Self::open_level_selection();
+                if let MenuState::LevelSelect(level_select) = &mut menu {
+                    level_select.error = Some(err);
+                }
+                menu
+            }
+        }
     }
 
     fn handle_esc(&mut self, _window: WindowId) -> UpdateAction {
         match self {
-            MenuState::InGame {
-                state: _state,
-                paused: true,
-            } => *self = Self::open_level_selection(),
+            MenuState::InGame { paused: true, .. } => *self = Self::open_level_selection(),
             MenuState::LevelSelect(_) => {
                 return UpdateAction::Close;
             }
             InGame {
-                state: _state,
                 paused: paused @ false,
+                ..
             } => *paused = true,
         }
 
@@ -72,12 +152,13 @@ impl MenuState {
         match self {
             MenuState::InGame {
                 state: game_state,
-                paused: _,
+                camera,
+                ..
             } => {
                 let draw = app.draw();
                 draw.background().color(IN_GAME_BACKGROUND);
                 draw.to_frame(app, frame).unwrap();
-                game_state.draw_game(app, frame, egui, texture_map);
+                game_state.draw_game(app, frame, egui, texture_map, camera);
                 egui.draw_to_frame(frame).unwrap();
             }
             _ => {
@@ -91,7 +172,7 @@ impl MenuState {
 
     pub(crate) fn update(
         &mut self,
-        _app: &App,
+        app: &App,
         update: Update,
         ctx: &mut FrameCtx,
         main_window: WindowId,
@@ -104,8 +185,8 @@ impl MenuState {
 
         match self {
             MenuState::InGame {
-                state: _,
                 paused: paused @ true,
+                ..
             } => {
                 let back = egui::Window::new("Pause Menu")
                     .show(ctx, |ui| {
@@ -130,13 +211,39 @@ impl MenuState {
                         ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
                             ui.label("Level Selection");
 
+                            if let Some(error) = &level_list.error {
+                                ui.colored_label(egui::Color32::RED, error);
+                            }
+
                             ui.group(|ui| {
-                                for level in level_list.0.iter() {
-                                    if ui.button(&level.name).clicked() {
-                                        return Some(level.clone());
-                                    }
+                                let mut selected = None;
+                                for level in level_list.levels.iter() {
+                                    ui.horizontal(|ui| {
+                                        if ui.button(&level.name).clicked() {
+                                            selected = Some(level.clone());
+                                        }
+                                        if let Some(best) =
+                                            level_list.progress.best.get(&level.name)
+                                        {
+                                            ui.label(format!(
+                                                "{} {:.1}s {} moves",
+                                                stars(best.stars),
+                                                best.ticks as f32 / TICK_RATE as f32,
+                                                best.moves
+                                            ));
+                                        }
+                                    });
+                                }
+                                for broken in &level_list.broken {
+                                    ui.horizontal(|ui| {
+                                        ui.add_enabled(false, egui::Button::new(&broken.file_name));
+                                        ui.colored_label(
+                                            egui::Color32::RED,
+                                            broken.error.to_string(),
+                                        );
+                                    });
                                 }
-                                None
+                                selected
                             })
                             .inner
                         })
@@ -144,33 +251,84 @@ impl MenuState {
                     .inner;
 
                 if let Some(level) = result.inner {
-                    *self = MenuState::InGame {
-                        state: GameState::new(level),
-                        paused: false,
-                    }
+                    *self = Self::start_level(level, level_list.levels.clone());
                 }
                 UpdateAction::Nothing
             }
             MenuState::InGame {
                 state,
                 paused: false,
+                replay,
+                camera,
+                levels,
             } => {
-                match state {
-                    GameState::Won { .. } => {
+                match &mut **state {
+                    GameState::Won {
+                        level_template,
+                        result,
+                    } => {
+                        let mut retry = false;
+                        let mut next = false;
+                        let mut exit = false;
                         egui::Window::new("Won").show(ctx, |ui| {
                             ui.label("Congratulations!");
-                            if ui.button("Retry Level").clicked() {
-                                // TODO
+                            ui.label(stars(level_template.par.stars(result)));
+                            ui.label(format!("{:.1}s, {} moves", result.seconds(), result.moves));
+                            if result.secrets > 0 {
+                                ui.label(format!(
+                                    "Secrets found: {}/{}",
+                                    result.secrets_found, result.secrets
+                                ));
                             }
-                            if ui.button("Exit Level").clicked() {
-                                // TODO
+                            retry = ui.button("Retry Level").clicked();
+                            next = ui.button("Next Level").clicked();
+                            exit = ui.button("Exit Level").clicked();
+                            if ui.button("Save Replay").clicked() {
+                                let path =
+                                    replay::replay_path(&get_asset_path(), &replay.level_name);
+                                if let Err(err) = replay::save_replay(&path, replay) {
+                                    error!("Failed to save the replay: {}", err);
+                                }
                             }
                         });
 
+                        if retry {
+                            *self = Self::start_level(level_template.clone(), levels.clone());
+                        } else if next {
+                            *self = match loading::next_level(levels, level_template) {
+                                Some(level) => Self::start_level(level.clone(), levels.clone()),
+                                None => Self::open_level_selection(),
+                            };
+                        } else if exit {
+                            *self = Self::open_level_selection();
+                        }
+
+                        UpdateAction::Nothing
+                    }
+                    GameState::Lost { level_template } => {
+                        let mut retry = false;
+                        let mut exit = false;
+                        egui::Window::new("Lost").show(ctx, |ui| {
+                            ui.label("You were caught by a shark!");
+                            retry = ui.button("Retry Level").clicked();
+                            exit = ui.button("Exit Level").clicked();
+                        });
+
+                        if retry {
+                            *self = Self::start_level(level_template.clone(), levels.clone());
+                        } else if exit {
+                            *self = Self::open_level_selection();
+                        }
+
                         UpdateAction::Nothing
                     }
                     GameState::GameState {
-                        show_hud, rotation, ..
+                        show_hud,
+                        position,
+                        moves,
+                        inventory,
+                        elapsed,
+                        ..
                     } => {
                         // FIXME should be F1, but egui in the version used be nannou_egui does not have that key
                         if ctx.input(|state| state.key_pressed(Key::H)) {
@@ -180,28 +338,60 @@ impl MenuState {
                         if *show_hud {
                             egui::Window::new("").show(ctx, |ui| {
                                 ui.label("HUD");
+                                ui.label(format!("Floor {}", position.z));
+                                ui.label(format!(
+                                    "Time {:.1}s",
+                                    *elapsed as f32 / TICK_RATE as f32
+                                ));
+                                ui.label(format!("Moves {}", moves.moves));
+                                ui.label(format!("Undos {}", moves.undos));
+                                for (color, count) in &inventory.keys {
+                                    ui.label(format!("{:?} Key x{}", color, count));
+                                }
                             });
                         }
 
-                        // Rotate 2 radians per second.
-                        let delta: f32 = update.since_last.secs() as f32;
-                        *rotation += 8.0 * delta;
-
                         let mut key_map: BTreeMap<Key, Action> = BTreeMap::new();
 
                         key_map.insert(Key::W, Action::Up);
                         key_map.insert(Key::A, Action::Left);
                         key_map.insert(Key::S, Action::Down);
                         key_map.insert(Key::D, Action::Right);
+                        key_map.insert(Key::E, Action::Ascend);
+                        key_map.insert(Key::Q, Action::Descend);
+                        key_map.insert(Key::Z, Action::Undo);
 
-                        ctx.input(|input_state| {
+                        let inputs: InputSet = ctx.input(|input_state| {
                             key_map
                                 .iter()
                                 .filter(|(&k, _)| input_state.key_down(k))
-                                .for_each(|(_, action)| action.perform(state));
+                                .map(|(_, &action)| action)
+                                .collect()
                         });
 
-                        state.handle_input();
+                        // egui reports scrolling in points, about 50 per line on the mouse wheel
+                        if !ctx.is_pointer_over_area() {
+                            camera.zoom_by(
+                                ctx.input(|input_state| input_state.scroll_delta.y) / 50.0,
+                            );
+                        }
+
+                        let dt = update.since_last.secs() as f32;
+                        let ticks = state.step(&inputs, dt);
+                        replay.record(&inputs, ticks);
+
+                        if let GameState::GameState { position, .. } = &**state {
+                            let window = app.window_rect();
+                            camera.follow(position, dt, window.w(), window.h());
+                        }
+
+                        if let GameState::Won {
+                            level_template,
+                            result,
+                        } = &**state
+                        {
+                            Self::record_win(level_template, result);
+                        }
 
                         UpdateAction::Nothing
                     }
@@ -210,3 +400,10 @@ impl MenuState {
         }
     }
 }
+
+// filled and empty stars for a rating out of three
+fn stars(count: u8) -> String {
+    (0..3)
+        .map(|star| if star < count { '\u{2605}' } else { '\u{2606}' })
+        .collect()
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::{goal_active, hold, walk_to};
+use learning_conrod_game::game::{
+    ButtonKind, GameState, InputSet, LevelTemplate, ObjectCoordinate, TileType,
+};
+use std::rc::Rc;
+
+const BUTTON: ObjectCoordinate = ObjectCoordinate { x: 1, y: 0, z: 0 };
+const GOAL: ObjectCoordinate = ObjectCoordinate { x: 5, y: 1, z: 0 };
+
+// a corridor with a button at x = 1 that activates a goal off to the side
+fn corridor(kind: ButtonKind) -> Rc<LevelTemplate> {
+    let mut tile_map = common::corridor(4);
+    tile_map.insert(
+        BUTTON,
+        TileType::Button {
+            pressed: false,
+            inverted: false,
+            targets: vec![GOAL],
+            kind,
+        },
+    );
+    tile_map.insert(GOAL, TileType::Goal { active: false });
+    Rc::new(common::level("Buttons", tile_map))
+}
+
+#[test]
+fn pressure_plates_are_only_pressed_while_occupied() {
+    let mut state = GameState::new(corridor(ButtonKind::PressurePlate)).unwrap();
+
+    walk_to(&mut state, 1);
+    state.tick(&InputSet::new());
+    assert!(goal_active(&state, GOAL));
+
+    walk_to(&mut state, 2);
+    state.tick(&InputSet::new());
+    assert!(!goal_active(&state, GOAL));
+}
+
+#[test]
+fn timed_buttons_release_after_their_duration() {
+    let mut state = GameState::new(corridor(ButtonKind::Timed {
+        duration: 300,
+        remaining: 0,
+    }))
+    .unwrap();
+
+    walk_to(&mut state, 1);
+    walk_to(&mut state, 2);
+    assert!(goal_active(&state, GOAL));
+
+    hold(&mut state, &[], 300);
+    assert!(!goal_active(&state, GOAL));
+}
+
+#[test]
+fn toggle_buttons_stay_pressed() {
+    let mut state = GameState::new(corridor(ButtonKind::Toggle)).unwrap();
+
+    walk_to(&mut state, 2);
+    hold(&mut state, &[], 30);
+    assert!(goal_active(&state, GOAL));
+}
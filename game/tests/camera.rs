@@ -0,0 +1,94 @@
+use learning_conrod_game::game::camera::{Bounds, Camera, MAX_ZOOM, MIN_ZOOM};
+use learning_conrod_game::game::{
+    LevelState, ObjectCoordinate, PlayerCoordinate, TileType, TILE_SIZE,
+};
+use std::collections::BTreeMap;
+
+fn player(x: f32, y: f32) -> PlayerCoordinate {
+    PlayerCoordinate { x, y, z: 0 }
+}
+
+// a level covering x from 0 to 19 and y from 0 to 9
+fn bounds() -> Option<Bounds> {
+    let mut tile_map = BTreeMap::new();
+    tile_map.insert(ObjectCoordinate { x: 0, y: 0, z: 0 }, TileType::Path);
+    tile_map.insert(ObjectCoordinate { x: 19, y: 9, z: 0 }, TileType::Path);
+    Bounds::of_level(&LevelState {
+        tile_map,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn bounds_cover_every_tile() {
+    assert_eq!(
+        bounds(),
+        Some(Bounds {
+            min_x: -0.5,
+            min_y: -0.5,
+            max_x: 19.5,
+            max_y: 9.5,
+        })
+    );
+    assert_eq!(Bounds::of_level(&LevelState::default()), None);
+}
+
+#[test]
+fn the_camera_catches_up_with_the_player() {
+    let mut camera = Camera::new(None);
+    camera.jump_to(&player(0.0, 0.0));
+
+    camera.follow(&player(4.0, 2.0), 1.0 / 60.0, 640.0, 640.0);
+    assert!(camera.x > 0.0 && camera.x < 4.0);
+    assert!(camera.y > 0.0 && camera.y < 2.0);
+
+    for _ in 0..300 {
+        camera.follow(&player(4.0, 2.0), 1.0 / 60.0, 640.0, 640.0);
+    }
+    assert!((camera.x - 4.0).abs() < 0.01);
+    assert!((camera.y - 2.0).abs() < 0.01);
+}
+
+#[test]
+fn screen_positions_are_relative_to_the_camera() {
+    let mut camera = Camera::new(None);
+    camera.jump_to(&player(4.0, 2.0));
+    // y points down in the level but up on screen
+    assert_eq!(camera.to_screen(5.0, 1.0), (TILE_SIZE, TILE_SIZE));
+
+    camera.zoom = 2.0;
+    assert_eq!(camera.to_screen(3.0, 2.0), (-2.0 * TILE_SIZE, 0.0));
+}
+
+#[test]
+fn the_camera_stays_inside_the_level() {
+    // five by five tiles fit into the window
+    let (w, h) = (5.0 * TILE_SIZE, 5.0 * TILE_SIZE);
+    let mut camera = Camera::new(bounds());
+
+    camera.jump_to(&player(0.0, 0.0));
+    camera.clamp(w, h);
+    assert_eq!((camera.x, camera.y), (2.0, 2.0));
+
+    camera.jump_to(&player(19.0, 9.0));
+    camera.clamp(w, h);
+    assert_eq!((camera.x, camera.y), (17.0, 7.0));
+
+    // the whole level fits into the window when zoomed out, so it is centered
+    camera.zoom = 0.25;
+    camera.clamp(w, h);
+    assert_eq!((camera.x, camera.y), (9.5, 4.5));
+}
+
+#[test]
+fn zoom_is_limited() {
+    let mut camera = Camera::new(None);
+    camera.zoom_by(1.0);
+    assert!(camera.zoom > 1.0);
+    assert!(camera.tile_size() > TILE_SIZE);
+
+    camera.zoom_by(1000.0);
+    assert_eq!(camera.zoom, MAX_ZOOM);
+    camera.zoom_by(-1000.0);
+    assert_eq!(camera.zoom, MIN_ZOOM);
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use common::{at, walk, wall};
+use learning_conrod_game::game::collision::{move_player, PLAYER_HALF_EXTENT};
+use learning_conrod_game::game::{
+    Direction, GateVisibility, LevelState, PlayerCoordinate, RevealTrigger, TileType,
+};
+use std::collections::BTreeMap;
+
+fn gate(open: bool) -> TileType {
+    TileType::Gate {
+        open,
+        facing: Direction::NORTH,
+        hidden: GateVisibility::Visible,
+        reveal: RevealTrigger::Never,
+    }
+}
+
+fn level(tiles: &[((i64, i64), TileType)]) -> LevelState {
+    let tile_map: BTreeMap<_, _> = tiles
+        .iter()
+        .cloned()
+        .map(|((x, y), tile)| (at(x, y), tile))
+        .collect();
+    LevelState {
+        tile_map,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn walls_block_movement() {
+    let level = level(&[((0, 0), TileType::Path), ((1, 0), wall())]);
+    let mut position = PlayerCoordinate {
+        x: 0.0,
+        y: 0.0,
+        z: 0,
+    };
+
+    walk(&mut position, 0.1, 0.0, 20, &level);
+
+    assert!((position.x - (0.5 - PLAYER_HALF_EXTENT)).abs() < 1e-4);
+    assert_eq!(position.y, 0.0);
+}
+
+#[test]
+fn closed_gates_block_and_open_gates_do_not() {
+    let closed = level(&[((0, 0), TileType::Path), ((0, 1), gate(false))]);
+    let mut position = PlayerCoordinate {
+        x: 0.0,
+        y: 0.0,
+        z: 0,
+    };
+    assert_eq!(
+        move_player(&mut position, 0.0, 1.0, &closed),
+        vec![at(0, 1)]
+    );
+    assert!(position.y < 0.5);
+
+    let open = level(&[((0, 0), TileType::Path), ((0, 1), gate(true))]);
+    let mut position = PlayerCoordinate {
+        x: 0.0,
+        y: 0.0,
+        z: 0,
+    };
+    assert!(move_player(&mut position, 0.0, 1.0, &open).is_empty());
+    assert_eq!(position.y, 1.0);
+}
+
+#[test]
+fn diagonal_movement_slides_along_walls() {
+    let level = level(&[
+        ((0, 0), TileType::Path),
+        ((1, 0), TileType::Path),
+        ((0, 1), wall()),
+        ((1, 1), wall()),
+    ]);
+    let mut position = PlayerCoordinate {
+        x: 0.0,
+        y: 0.0,
+        z: 0,
+    };
+
+    walk(&mut position, 0.05, 0.05, 10, &level);
+
+    assert!((position.x - 0.5).abs() < 1e-4);
+    assert!((position.y - (0.5 - PLAYER_HALF_EXTENT)).abs() < 1e-4);
+}
+
+#[test]
+fn player_can_pass_through_gaps_of_one_tile() {
+    let level = level(&[
+        ((1, -1), wall()),
+        ((1, 0), TileType::Path),
+        ((1, 1), wall()),
+    ]);
+    let mut position = PlayerCoordinate {
+        x: 0.0,
+        y: 0.0,
+        z: 0,
+    };
+
+    walk(&mut position, 0.1, 0.0, 20, &level);
+
+    assert!((position.x - 2.0).abs() < 1e-4);
+}
+
+#[test]
+fn player_inside_a_wall_can_leave_it() {
+    let level = level(&[((0, 0), wall())]);
+    let mut position = PlayerCoordinate {
+        x: 0.0,
+        y: 0.0,
+        z: 0,
+    };
+
+    assert!(move_player(&mut position, 1.0, 0.0, &level).is_empty());
+    assert_eq!(position.x, 1.0);
+}
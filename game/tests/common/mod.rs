@@ -0,0 +1,102 @@
+// fixtures shared by the integration tests, not every test uses all of them
+#![allow(dead_code)]
+
+use learning_conrod_game::game::collision::move_player;
+use learning_conrod_game::game::{
+    Action, GameState, InputSet, LevelState, LevelTemplate, ObjectCoordinate, PlayerCoordinate,
+    TileType, WallType,
+};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+pub fn at(x: i64, y: i64) -> ObjectCoordinate {
+    ObjectCoordinate { x, y, z: 0 }
+}
+
+pub fn wall() -> TileType {
+    TileType::Wall {
+        kind: WallType::Lone,
+    }
+}
+
+// paths from x = 0 to x = length - 1 with the start at x = 0
+pub fn corridor(length: i64) -> BTreeMap<ObjectCoordinate, TileType> {
+    let mut tile_map: BTreeMap<_, _> = (0..length).map(|x| (at(x, 0), TileType::Path)).collect();
+    tile_map.insert(at(0, 0), TileType::Start);
+    tile_map
+}
+
+pub fn level(name: &str, tile_map: BTreeMap<ObjectCoordinate, TileType>) -> LevelTemplate {
+    LevelTemplate::new(
+        name,
+        LevelState {
+            tile_map,
+            ..Default::default()
+        },
+    )
+}
+
+pub fn inputs(actions: &[Action]) -> InputSet {
+    actions.iter().copied().collect()
+}
+
+// holds `actions` for a number of ticks, holding nothing just waits
+pub fn hold(state: &mut GameState, actions: &[Action], ticks: u32) {
+    let inputs = inputs(actions);
+    for _ in 0..ticks {
+        state.tick(&inputs);
+    }
+}
+
+// presses `action` for a single tick
+pub fn press(state: &mut GameState, action: Action) {
+    state.tick(&inputs(&[action]));
+    state.tick(&InputSet::new());
+}
+
+// walks right until the player stands on the tile at x
+pub fn walk_to(state: &mut GameState, x: i64) {
+    let right = inputs(&[Action::Right]);
+    while let GameState::GameState { old_position, .. } = state {
+        if old_position.x >= x {
+            break;
+        }
+        state.tick(&right);
+    }
+}
+
+// moves the player by (dx, dy) a number of times, bypassing the simulation
+pub fn walk(position: &mut PlayerCoordinate, dx: f32, dy: f32, steps: usize, level: &LevelState) {
+    for _ in 0..steps {
+        move_player(position, dx, dy, level);
+    }
+}
+
+// the tile the player stands on
+pub fn tile(state: &GameState) -> ObjectCoordinate {
+    match state {
+        GameState::GameState { old_position, .. } => *old_position,
+        _ => panic!("the game should still be running"),
+    }
+}
+
+pub fn x(state: &GameState) -> f32 {
+    match state {
+        GameState::GameState { position, .. } => position.x,
+        _ => panic!("the game should still be running"),
+    }
+}
+
+pub fn goal_active(state: &GameState, goal: ObjectCoordinate) -> bool {
+    match state {
+        GameState::GameState { level_state, .. } => matches!(
+            level_state.tile_map.get(&goal),
+            Some(TileType::Goal { active: true })
+        ),
+        _ => panic!("the game should still be running"),
+    }
+}
+
+pub fn same(a: &LevelTemplate, b: &LevelTemplate) -> bool {
+    ron::to_string(a).unwrap() == ron::to_string(b).unwrap()
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::{at, hold, press, walk_to, wall};
+use learning_conrod_game::game::{
+    Action, GameState, InputSet, LevelTemplate, ObjectCoordinate, TileType,
+};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+// two floors connected by a ladder at the origin with the start just west of it,
+// the tile east of the ladder is a wall on the ground floor but open on the first floor
+fn two_floors() -> Rc<LevelTemplate> {
+    let mut tile_map = BTreeMap::new();
+    tile_map.insert(at(-1, 0), TileType::Start);
+    tile_map.insert(at(0, 0), TileType::Ladder);
+    tile_map.insert(at(1, 0), wall());
+    tile_map.insert(ObjectCoordinate { x: 0, y: 0, z: 1 }, TileType::Ladder);
+    tile_map.insert(ObjectCoordinate { x: 1, y: 0, z: 1 }, TileType::Path);
+    Rc::new(common::level("Floors", tile_map))
+}
+
+// starts the level and walks onto the ladder
+fn on_ladder() -> GameState {
+    let mut state = GameState::new(two_floors()).unwrap();
+    walk_to(&mut state, 0);
+    state.tick(&InputSet::new());
+    state
+}
+
+fn floor(state: &GameState) -> i64 {
+    match state {
+        GameState::GameState { position, .. } => position.z,
+        _ => panic!("the game should still be running"),
+    }
+}
+
+#[test]
+fn ladders_move_the_player_between_floors() {
+    let mut state = on_ladder();
+
+    press(&mut state, Action::Ascend);
+    assert_eq!(floor(&state), 1);
+
+    press(&mut state, Action::Ascend);
+    assert_eq!(floor(&state), 1);
+
+    press(&mut state, Action::Descend);
+    assert_eq!(floor(&state), 0);
+}
+
+#[test]
+fn holding_the_climb_key_climbs_only_once() {
+    let mut state = on_ladder();
+
+    hold(&mut state, &[Action::Ascend], 10);
+
+    assert_eq!(floor(&state), 1);
+}
+
+#[test]
+fn collision_only_considers_the_current_floor() {
+    let mut ground = on_ladder();
+    hold(&mut ground, &[Action::Right], 200);
+
+    let mut upper = on_ladder();
+    press(&mut upper, Action::Ascend);
+    hold(&mut upper, &[Action::Right], 200);
+
+    match (ground, upper) {
+        (GameState::GameState { position: g, .. }, GameState::GameState { position: u, .. }) => {
+            assert!(g.x < 0.5);
+            assert!(u.x > 1.0);
+        }
+        _ => panic!("the game should still be running"),
+    }
+}
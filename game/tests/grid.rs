@@ -0,0 +1,90 @@
+mod common;
+
+use common::{at, hold, press, wall, x};
+use learning_conrod_game::game::grid::GRID_MOVE_TICKS;
+use learning_conrod_game::game::simulation::TICK_RATE;
+use learning_conrod_game::game::{Action, GameState, LevelTemplate, MovementMode};
+use std::rc::Rc;
+
+// a grid movement corridor from x = 0 to x = 3 closed off by a wall
+fn corridor() -> Rc<LevelTemplate> {
+    let mut tile_map = common::corridor(4);
+    tile_map.insert(at(4, 0), wall());
+    let mut level = common::level("Grid", tile_map);
+    level.movement = MovementMode::Grid;
+    Rc::new(level)
+}
+
+#[test]
+fn a_press_moves_exactly_one_tile() {
+    let mut state = GameState::new(corridor()).unwrap();
+
+    press(&mut state, Action::Right);
+    hold(&mut state, &[], GRID_MOVE_TICKS / 2 - 2);
+    assert!(x(&state) > 0.0 && x(&state) < 1.0);
+
+    hold(&mut state, &[], 2 * GRID_MOVE_TICKS);
+    assert_eq!(x(&state), 1.0);
+}
+
+#[test]
+fn holding_a_key_moves_only_once() {
+    let mut state = GameState::new(corridor()).unwrap();
+
+    hold(&mut state, &[Action::Right], 3 * GRID_MOVE_TICKS);
+
+    assert_eq!(x(&state), 1.0);
+}
+
+#[test]
+fn presses_during_a_move_are_buffered() {
+    let mut state = GameState::new(corridor()).unwrap();
+
+    press(&mut state, Action::Right);
+    press(&mut state, Action::Right);
+    hold(&mut state, &[], 3 * GRID_MOVE_TICKS);
+
+    assert_eq!(x(&state), 2.0);
+}
+
+#[test]
+fn walls_stop_grid_moves() {
+    let mut state = GameState::new(corridor()).unwrap();
+
+    for _ in 0..6 {
+        press(&mut state, Action::Right);
+        hold(&mut state, &[], GRID_MOVE_TICKS);
+    }
+
+    assert_eq!(x(&state), 3.0);
+}
+
+#[test]
+fn missing_tiles_are_open_in_both_movement_modes() {
+    for movement in [MovementMode::Free, MovementMode::Grid] {
+        let mut level = (*corridor()).clone();
+        level.init_state.tile_map.remove(&at(2, 0));
+        level.movement = movement;
+        let mut state = GameState::new(Rc::new(level)).unwrap();
+
+        match movement {
+            MovementMode::Free => {
+                hold(&mut state, &[Action::Right], 10 * TICK_RATE);
+            }
+            MovementMode::Grid => {
+                for _ in 0..6 {
+                    press(&mut state, Action::Right);
+                    hold(&mut state, &[], GRID_MOVE_TICKS);
+                }
+            }
+        }
+
+        // across the gap and up to the wall
+        assert!(
+            x(&state) > 2.5,
+            "{:?} movement stopped at {}",
+            movement,
+            x(&state)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::{at, hold, x};
+use learning_conrod_game::game::{Action, GameState, KeyColor, LevelTemplate, TileType};
+use std::rc::Rc;
+
+// a corridor with a red key at x = 1 and a door at x = 3
+fn corridor(door_color: KeyColor, consumes: bool) -> Rc<LevelTemplate> {
+    let mut tile_map = common::corridor(6);
+    tile_map.insert(
+        at(1, 0),
+        TileType::Key {
+            color: KeyColor::Red,
+        },
+    );
+    tile_map.insert(
+        at(3, 0),
+        TileType::Door {
+            color: door_color,
+            open: false,
+            consumes,
+        },
+    );
+    Rc::new(common::level("Keys", tile_map))
+}
+
+fn keys(state: &GameState) -> u32 {
+    match state {
+        GameState::GameState { inventory, .. } => {
+            inventory.keys.get(&KeyColor::Red).copied().unwrap_or(0)
+        }
+        _ => panic!("the game should still be running"),
+    }
+}
+
+#[test]
+fn keys_are_picked_up() {
+    let mut state = GameState::new(corridor(KeyColor::Red, true)).unwrap();
+
+    hold(&mut state, &[Action::Right], 150);
+
+    assert_eq!(keys(&state), 1);
+    match &state {
+        GameState::GameState { level_state, .. } => {
+            assert!(matches!(
+                level_state.tile_map.get(&at(1, 0)),
+                Some(TileType::Path)
+            ))
+        }
+        _ => panic!("the game should still be running"),
+    }
+}
+
+#[test]
+fn doors_open_with_a_matching_key() {
+    let mut state = GameState::new(corridor(KeyColor::Red, true)).unwrap();
+
+    hold(&mut state, &[Action::Right], 600);
+
+    assert!(x(&state) > 3.0);
+    assert_eq!(keys(&state), 0);
+}
+
+#[test]
+fn doors_can_keep_the_key() {
+    let mut state = GameState::new(corridor(KeyColor::Red, false)).unwrap();
+
+    hold(&mut state, &[Action::Right], 600);
+
+    assert!(x(&state) > 3.0);
+    assert_eq!(keys(&state), 1);
+}
+
+#[test]
+fn doors_stay_closed_without_a_matching_key() {
+    let mut state = GameState::new(corridor(KeyColor::Blue, true)).unwrap();
+
+    hold(&mut state, &[Action::Right], 600);
+
+    assert!(x(&state) < 2.5);
+    assert_eq!(keys(&state), 1);
+}
+
+#[test]
+fn undo_returns_picked_up_keys() {
+    let mut state = GameState::new(corridor(KeyColor::Red, true)).unwrap();
+
+    hold(&mut state, &[Action::Right], 150);
+    while keys(&state) > 0 {
+        state.undo();
+    }
+
+    match &state {
+        GameState::GameState { level_state, .. } => assert!(matches!(
+            level_state.tile_map.get(&at(1, 0)),
+            Some(TileType::Key { .. })
+        )),
+        _ => panic!("the game should still be running"),
+    }
+}
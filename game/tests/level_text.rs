@@ -0,0 +1,137 @@
+mod common;
+
+use common::{at, same};
+use learning_conrod_game::game::level::loading::{load_levels, LoadingError};
+use learning_conrod_game::game::level_text::{from_text, to_text};
+use learning_conrod_game::game::test_level::test_level;
+use learning_conrod_game::game::{
+    Direction, LevelState, LevelTemplate, ObjectCoordinate, Orientation, TileType, WallType,
+};
+
+const CORRIDOR: &str = r#"(
+    name: "Corridor",
+    init_state: (
+        tile_map: {
+            (x: 2, y: 1): Button(
+                pressed: false,
+                inverted: false,
+                targets: [(x: 3, y: 1)],
+            ),
+        },
+    ),
+)
+floor 0 at 0 0
+#####
+#.BgS
+#####
+"#;
+
+#[test]
+fn text_levels_are_read_from_the_grid() {
+    let level = from_text(CORRIDOR).unwrap_or_else(|err| panic!("{}", err));
+    let tiles = &level.init_state.tile_map;
+
+    assert_eq!(level.name, "Corridor");
+    assert_eq!(tiles.len(), 15);
+    assert_eq!(tiles.get(&at(4, 1)), Some(&TileType::Start));
+    assert_eq!(
+        tiles.get(&at(3, 1)),
+        Some(&TileType::Goal { active: false })
+    );
+    assert!(matches!(
+        tiles.get(&at(2, 1)),
+        Some(TileType::Button { targets, .. }) if targets == &vec![at(3, 1)]
+    ));
+    assert_eq!(
+        tiles.get(&at(2, 0)),
+        Some(&TileType::Wall {
+            kind: WallType::Double {
+                orientation: Orientation::Horizontal
+            }
+        })
+    );
+    assert_eq!(
+        tiles.get(&at(0, 1)),
+        Some(&TileType::Wall {
+            kind: WallType::Double {
+                orientation: Orientation::Vertical
+            }
+        })
+    );
+}
+
+#[test]
+fn levels_survive_the_text_format() {
+    let level = test_level();
+    let text = to_text(&level).unwrap();
+    let read = from_text(&text).unwrap_or_else(|err| panic!("{}", err));
+    assert!(same(&level, &read), "{}", text);
+
+    let mut floors = LevelTemplate::new("Floors", LevelState::default());
+    let tiles = &mut floors.init_state.tile_map;
+    tiles.insert(ObjectCoordinate { x: -2, y: -1, z: 0 }, TileType::Start);
+    tiles.insert(ObjectCoordinate { x: -2, y: 0, z: 0 }, TileType::Ladder);
+    tiles.insert(ObjectCoordinate { x: -2, y: 0, z: 1 }, TileType::Ladder);
+    tiles.insert(
+        ObjectCoordinate { x: 0, y: 0, z: 1 },
+        TileType::OneWay {
+            inverted: true,
+            facing: Direction::EAST,
+        },
+    );
+    tiles.insert(
+        ObjectCoordinate { x: 1, y: 3, z: 1 },
+        TileType::Goal { active: true },
+    );
+    let text = to_text(&floors).unwrap();
+    assert!(text.contains("floor 0 at -2 -1\nS\nH\n"), "{}", text);
+    assert!(
+        text.contains("floor 1 at -2 0\nH <\n\n\n   G\n"),
+        "{}",
+        text
+    );
+    let read = from_text(&text).unwrap_or_else(|err| panic!("{}", err));
+    assert!(same(&floors, &read), "{}", text);
+}
+
+#[test]
+fn unknown_characters_are_reported_with_their_line() {
+    let broken = CORRIDOR.replace("#.BgS", "#.BgX");
+    match from_text(&broken) {
+        Err(err @ LoadingError::Grid { line: 15, .. }) => {
+            assert_eq!(err.to_string(), "line 15: unknown tile 'X' in column 5")
+        }
+        Err(err) => panic!("unexpected error {}", err),
+        Ok(_) => panic!("the level should not load"),
+    }
+}
+
+#[test]
+fn floors_can_only_be_described_once() {
+    let twice = format!("{CORRIDOR}floor 0 at 0 5\n#.#\n");
+
+    match from_text(&twice) {
+        Err(err @ LoadingError::Grid { line: 17, .. }) => {
+            assert_eq!(err.to_string(), "line 17: floor 0 is described twice")
+        }
+        Err(err) => panic!("unexpected error {}", err),
+        Ok(_) => panic!("the level should not load"),
+    }
+}
+
+#[test]
+fn text_levels_are_loaded_by_extension() {
+    let assets = std::env::temp_dir().join(format!("learning_conrod_text_{}", std::process::id()));
+    let levels = assets.join("levels");
+    std::fs::create_dir_all(&levels).unwrap();
+    std::fs::write(levels.join("corridor.level.txt"), CORRIDOR).unwrap();
+    std::fs::write(levels.join("README.txt"), "Levels go here.").unwrap();
+
+    let directory = load_levels(&assets);
+    std::fs::remove_dir_all(&assets).unwrap();
+    let directory = directory.unwrap_or_else(|err| panic!("{}", err));
+
+    assert!(directory.broken.is_empty());
+    assert_eq!(directory.loaded.len(), 1);
+    assert_eq!(directory.loaded[0].name, "Corridor");
+}
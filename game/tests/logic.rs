@@ -0,0 +1,145 @@
+use learning_conrod_game::game::logic::{LogicNetwork, LogicNode, Signal};
+use learning_conrod_game::game::{
+    ButtonKind, Direction, GateVisibility, LevelState, ObjectCoordinate, RevealTrigger, TileType,
+};
+use std::collections::BTreeMap;
+
+const GATE: ObjectCoordinate = ObjectCoordinate { x: 0, y: 0, z: 0 };
+const LEFT: ObjectCoordinate = ObjectCoordinate { x: -1, y: 0, z: 0 };
+const RIGHT: ObjectCoordinate = ObjectCoordinate { x: 1, y: 0, z: 0 };
+
+fn button(pressed: bool, targets: Vec<ObjectCoordinate>) -> TileType {
+    TileType::Button {
+        pressed,
+        inverted: false,
+        targets,
+        kind: ButtonKind::Toggle,
+    }
+}
+
+// a gate with a button on either side
+fn level(left: TileType, right: TileType) -> LevelState {
+    let mut tile_map = BTreeMap::new();
+    tile_map.insert(
+        GATE,
+        TileType::Gate {
+            open: false,
+            facing: Direction::NORTH,
+            hidden: GateVisibility::Visible,
+            reveal: RevealTrigger::Never,
+        },
+    );
+    tile_map.insert(LEFT, left);
+    tile_map.insert(RIGHT, right);
+    LevelState {
+        tile_map,
+        ..Default::default()
+    }
+}
+
+fn gate_open(level: &LevelState) -> bool {
+    match level.tile_map.get(&GATE) {
+        Some(TileType::Gate { open, .. }) => *open,
+        _ => panic!("expected a gate"),
+    }
+}
+
+fn press(level: &mut LevelState, coord: ObjectCoordinate) {
+    if let Some(TileType::Button { pressed, .. }) = level.tile_map.get_mut(&coord) {
+        *pressed = !*pressed;
+    }
+}
+
+fn gate_driven_by(node: LogicNode) -> LogicNetwork {
+    let mut network = LogicNetwork::default();
+    network.nodes.insert(String::from("gate"), node);
+    network
+        .sinks
+        .insert(GATE, Signal::Node(String::from("gate")));
+    network
+}
+
+#[test]
+fn buttons_targeting_the_same_gate_are_combined() {
+    let mut level = level(button(true, vec![GATE]), button(false, vec![GATE]));
+    let network = LogicNetwork::default();
+
+    network.update(&mut level);
+    assert!(gate_open(&level));
+
+    press(&mut level, RIGHT);
+    network.update(&mut level);
+    assert!(gate_open(&level));
+
+    press(&mut level, LEFT);
+    press(&mut level, RIGHT);
+    network.update(&mut level);
+    assert!(!gate_open(&level));
+}
+
+#[test]
+fn and_requires_every_input() {
+    let mut level = level(button(true, vec![]), button(false, vec![]));
+    let network = gate_driven_by(LogicNode::And(vec![
+        Signal::Button(LEFT),
+        Signal::Button(RIGHT),
+    ]));
+
+    network.update(&mut level);
+    assert!(!gate_open(&level));
+
+    press(&mut level, RIGHT);
+    network.update(&mut level);
+    assert!(gate_open(&level));
+}
+
+#[test]
+fn xor_and_not_combine() {
+    let mut level = level(button(true, vec![]), button(true, vec![]));
+    let mut network = gate_driven_by(LogicNode::Not(Signal::Node(String::from("xor"))));
+    network.nodes.insert(
+        String::from("xor"),
+        LogicNode::Xor(vec![Signal::Button(LEFT), Signal::Button(RIGHT)]),
+    );
+
+    network.update(&mut level);
+    assert!(gate_open(&level));
+
+    press(&mut level, LEFT);
+    network.update(&mut level);
+    assert!(!gate_open(&level));
+}
+
+#[test]
+fn delay_holds_back_the_signal() {
+    let mut level = level(button(false, vec![]), button(false, vec![]));
+    let network = gate_driven_by(LogicNode::Delay {
+        input: Signal::Button(LEFT),
+        ticks: 3,
+    });
+
+    network.update(&mut level);
+    press(&mut level, LEFT);
+    for _ in 0..3 {
+        network.update(&mut level);
+        assert!(!gate_open(&level));
+    }
+    network.update(&mut level);
+    assert!(gate_open(&level));
+}
+
+#[test]
+fn cycles_without_delay_do_not_hang() {
+    let mut level = level(button(false, vec![]), button(false, vec![]));
+    let mut network = gate_driven_by(LogicNode::Not(Signal::Node(String::from("loop"))));
+    network.nodes.insert(
+        String::from("loop"),
+        LogicNode::Or(vec![Signal::Node(String::from("gate"))]),
+    );
+
+    // the signal closing the cycle reads as false, so loop is false and the gate opens
+    network.update(&mut level);
+    assert!(gate_open(&level));
+    network.update(&mut level);
+    assert!(gate_open(&level));
+}
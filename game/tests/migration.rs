@@ -0,0 +1,70 @@
+use learning_conrod_game::game::level::loading::{parse_level, LoadingError};
+use learning_conrod_game::game::migration::{file_version, migrate, MigrationError, LEVEL_VERSION};
+use learning_conrod_game::game::{LevelState, LevelTemplate, ObjectCoordinate, TileType};
+
+// a level as written before levels had a version, buttons had a single target back then
+const UNVERSIONED: &str = r#"(
+    name: "Old target: level",
+    init_state: (
+        tile_map: {
+            (x: 0, y: 0): Start,
+            (x: 1, y: 0): Button(
+                pressed: false,
+                inverted: false,
+                target: (x: 2, y: 0),
+            ),
+            (x: 2, y: 0): Goal(active: false),
+        },
+    ),
+)"#;
+
+#[test]
+fn unversioned_levels_are_upgraded() {
+    assert_eq!(file_version(UNVERSIONED).unwrap(), 0);
+
+    let level = parse_level(UNVERSIONED).unwrap_or_else(|_| panic!("the level should load"));
+    assert_eq!(level.version, LEVEL_VERSION);
+    assert_eq!(level.name, "Old target: level");
+    match level
+        .init_state
+        .tile_map
+        .get(&ObjectCoordinate { x: 1, y: 0, z: 0 })
+    {
+        Some(TileType::Button { targets, .. }) => {
+            assert_eq!(targets, &vec![ObjectCoordinate { x: 2, y: 0, z: 0 }])
+        }
+        other => panic!("expected a button, found {:?}", other),
+    }
+}
+
+#[test]
+fn current_levels_are_left_alone() {
+    let mut level = LevelTemplate::new("Current", LevelState::default());
+    level.init_state.tile_map.insert(
+        ObjectCoordinate { x: 0, y: 0, z: 0 },
+        TileType::Button {
+            pressed: false,
+            inverted: false,
+            targets: vec![ObjectCoordinate { x: 1, y: 0, z: 0 }],
+            kind: Default::default(),
+        },
+    );
+    let content = ron::to_string(&level).unwrap();
+
+    assert_eq!(file_version(&content).unwrap(), LEVEL_VERSION);
+    assert_eq!(migrate(&content, LEVEL_VERSION).unwrap(), content);
+}
+
+#[test]
+fn newer_levels_are_rejected() {
+    let content = format!(
+        r#"(version: {}, name: "Future", init_state: (tile_map: {{}}))"#,
+        LEVEL_VERSION + 1
+    );
+    match parse_level(&content) {
+        Err(LoadingError::Migration(MigrationError::Unsupported { version })) => {
+            assert_eq!(version, LEVEL_VERSION + 1)
+        }
+        _ => panic!("a level from a newer version should not load"),
+    }
+}
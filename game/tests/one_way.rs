@@ -0,0 +1,94 @@
+mod common;
+
+use common::{at, walk};
+use learning_conrod_game::game::{
+    Direction, LevelState, ObjectCoordinate, PlayerCoordinate, TileType,
+};
+use std::collections::BTreeMap;
+
+// a corridor from x = -1 to x = 1 with a OneWay in the middle
+fn corridor(facing: Direction) -> LevelState {
+    let mut tile_map = BTreeMap::new();
+    tile_map.insert(at(-1, 0), TileType::Path);
+    tile_map.insert(
+        at(0, 0),
+        TileType::OneWay {
+            inverted: false,
+            facing,
+        },
+    );
+    tile_map.insert(at(1, 0), TileType::Path);
+    tile_map.insert(at(0, 1), TileType::Path);
+    LevelState {
+        tile_map,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn one_way_can_be_passed_along_its_facing() {
+    let level = corridor(Direction::EAST);
+    let mut position = PlayerCoordinate {
+        x: -1.0,
+        y: 0.0,
+        z: 0,
+    };
+
+    walk(&mut position, 0.05, 0.0, 40, &level);
+
+    assert_eq!(ObjectCoordinate::from(&position), at(1, 0));
+}
+
+#[test]
+fn one_way_cannot_be_entered_against_its_facing() {
+    let level = corridor(Direction::EAST);
+    let mut position = PlayerCoordinate {
+        x: 1.0,
+        y: 0.0,
+        z: 0,
+    };
+
+    walk(&mut position, -0.05, 0.0, 40, &level);
+
+    assert_eq!(ObjectCoordinate::from(&position), at(1, 0));
+}
+
+#[test]
+fn one_way_cannot_be_left_sideways() {
+    let level = corridor(Direction::EAST);
+    let mut position = PlayerCoordinate {
+        x: 0.0,
+        y: 0.0,
+        z: 0,
+    };
+
+    walk(&mut position, 0.0, 0.05, 40, &level);
+
+    assert_eq!(ObjectCoordinate::from(&position), at(0, 0));
+}
+
+#[test]
+fn inverting_a_one_way_flips_its_direction() {
+    let mut level = corridor(Direction::EAST);
+    level
+        .tile_map
+        .get_mut(&at(0, 0))
+        .unwrap()
+        .apply_button(true);
+
+    let mut position = PlayerCoordinate {
+        x: -1.0,
+        y: 0.0,
+        z: 0,
+    };
+    walk(&mut position, 0.05, 0.0, 40, &level);
+    assert_eq!(ObjectCoordinate::from(&position), at(-1, 0));
+
+    let mut position = PlayerCoordinate {
+        x: 1.0,
+        y: 0.0,
+        z: 0,
+    };
+    walk(&mut position, -0.05, 0.0, 40, &level);
+    assert_eq!(ObjectCoordinate::from(&position), at(-1, 0));
+}
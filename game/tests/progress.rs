@@ -0,0 +1,128 @@
+use learning_conrod_game::game::progress::{
+    load_progress, record_win, save_progress, Par, Progress, RunResult,
+};
+use learning_conrod_game::game::simulation::TICK_RATE;
+use learning_conrod_game::game::{
+    Action, GameState, InputSet, LevelState, LevelTemplate, ObjectCoordinate, TileType,
+};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+fn result(seconds: u32, moves: u32) -> RunResult {
+    RunResult {
+        ticks: seconds * TICK_RATE,
+        moves,
+        secrets_found: 0,
+        secrets: 0,
+    }
+}
+
+#[test]
+fn par_values_award_stars() {
+    let par = Par {
+        time: Some(10.0),
+        moves: Some(5),
+    };
+
+    assert_eq!(par.stars(&result(8, 5)), 3);
+    assert_eq!(par.stars(&result(12, 5)), 2);
+    assert_eq!(par.stars(&result(8, 6)), 2);
+    assert_eq!(par.stars(&result(12, 6)), 1);
+    assert_eq!(Par::default().stars(&result(100, 100)), 3);
+}
+
+#[test]
+fn only_improvements_are_kept() {
+    let mut progress = Progress::default();
+
+    assert!(progress.record("Level", &result(10, 8), 2));
+    assert!(progress.record("Level", &result(12, 6), 1));
+    assert!(!progress.record("Level", &result(20, 20), 1));
+
+    let best = progress.best["Level"];
+    assert_eq!(best.stars, 2);
+    assert_eq!(best.ticks, 10 * TICK_RATE);
+    assert_eq!(best.moves, 6);
+}
+
+#[test]
+fn progress_survives_saving_and_loading() {
+    let path = std::env::temp_dir().join(format!(
+        "learning_conrod_progress_{}.ron",
+        std::process::id()
+    ));
+    let mut progress = Progress::default();
+    progress.record("Level", &result(10, 8), 2);
+
+    assert!(save_progress(&path, &progress).is_ok());
+    let loaded = load_progress(&path).unwrap_or_default();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.best, progress.best);
+}
+
+#[test]
+fn corrupt_save_files_are_not_overwritten() {
+    let path = std::env::temp_dir().join(format!(
+        "learning_conrod_corrupt_progress_{}.ron",
+        std::process::id()
+    ));
+    let corrupt = "(best: {\"Level\": (stars: 3, ticks:";
+    std::fs::write(&path, corrupt).unwrap();
+
+    let level = LevelTemplate::new("Progress", LevelState::default());
+    record_win(&path, &level, &result(10, 8));
+    let content = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(content, corrupt);
+}
+
+#[test]
+fn wins_are_saved() {
+    let path = std::env::temp_dir().join(format!(
+        "learning_conrod_won_progress_{}.ron",
+        std::process::id()
+    ));
+
+    let level = LevelTemplate::new("Progress", LevelState::default());
+    record_win(&path, &level, &result(10, 8));
+    let loaded = load_progress(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.best["Progress"].moves, 8);
+}
+
+#[test]
+fn winning_reports_time_and_moves() {
+    let mut tile_map = BTreeMap::new();
+    tile_map.insert(ObjectCoordinate { x: 0, y: 0, z: 0 }, TileType::Start);
+    tile_map.insert(ObjectCoordinate { x: 1, y: 0, z: 0 }, TileType::Path);
+    tile_map.insert(
+        ObjectCoordinate { x: 2, y: 0, z: 0 },
+        TileType::Goal { active: true },
+    );
+    let level = LevelTemplate::new(
+        "Progress",
+        LevelState {
+            tile_map,
+            ..Default::default()
+        },
+    );
+    let mut state = GameState::new(Rc::new(level)).unwrap();
+
+    let right: InputSet = [Action::Right].into_iter().collect();
+    let mut ticks = 0;
+    while let GameState::GameState { .. } = state {
+        state.tick(&right);
+        ticks += 1;
+    }
+
+    match state {
+        GameState::Won { result, .. } => {
+            assert_eq!(result.moves, 2);
+            assert_eq!(result.ticks, ticks);
+        }
+        _ => panic!("the level should be won"),
+    }
+}
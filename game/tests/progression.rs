@@ -0,0 +1,107 @@
+use learning_conrod_game::game::level::loading::{load_levels, next_level, LoadingError};
+use learning_conrod_game::game::{LevelState, LevelTemplate};
+
+#[test]
+fn levels_are_loaded_in_level_order() {
+    let assets = std::env::temp_dir().join(format!("learning_conrod_order_{}", std::process::id()));
+    let levels = assets.join("levels");
+    std::fs::create_dir_all(&levels).unwrap();
+
+    for (name, order) in [("Beta", 1), ("Alpha", 1), ("Tutorial", 0), ("Finale", 2)] {
+        let mut level = LevelTemplate::new(name, LevelState::default());
+        level.order = order;
+        std::fs::write(
+            levels.join(format!("{name}.ron")),
+            ron::to_string(&level).unwrap(),
+        )
+        .unwrap();
+    }
+
+    let loaded: Vec<_> = load_levels(&assets)
+        .unwrap_or_default()
+        .loaded
+        .into_iter()
+        .map(|level| level.name)
+        .collect();
+    std::fs::remove_dir_all(&assets).unwrap();
+
+    assert_eq!(loaded, vec!["Tutorial", "Alpha", "Beta", "Finale"]);
+}
+
+fn ordered(levels: &[(&str, i32)]) -> Vec<LevelTemplate> {
+    levels
+        .iter()
+        .map(|(name, order)| {
+            let mut level = LevelTemplate::new(*name, LevelState::default());
+            level.order = *order;
+            level
+        })
+        .collect()
+}
+
+fn name(level: Option<&LevelTemplate>) -> Option<&str> {
+    level.map(|level| level.name.as_str())
+}
+
+#[test]
+fn the_next_level_follows_in_level_order() {
+    let levels = ordered(&[("Tutorial", 0), ("Alpha", 1), ("Beta", 1), ("Finale", 2)]);
+
+    assert_eq!(name(next_level(&levels, &levels[0])), Some("Alpha"));
+    assert_eq!(name(next_level(&levels, &levels[2])), Some("Finale"));
+    assert_eq!(name(next_level(&levels, &levels[3])), None);
+}
+
+#[test]
+fn levels_sharing_a_name_are_told_apart_by_their_order() {
+    let levels = ordered(&[("Intro", 0), ("Cave", 1), ("Intro", 2), ("Finale", 3)]);
+
+    assert_eq!(name(next_level(&levels, &levels[2])), Some("Finale"));
+}
+
+#[test]
+fn missing_levels_are_followed_by_the_next_in_order() {
+    let levels = ordered(&[("Tutorial", 0), ("Finale", 2)]);
+    let removed = &ordered(&[("Removed", 1)])[0];
+
+    assert_eq!(name(next_level(&levels, removed)), Some("Finale"));
+    assert_eq!(name(next_level(&[] as &[LevelTemplate], removed)), None);
+}
+
+#[test]
+fn levels_without_an_order_default_to_zero() {
+    let level: LevelTemplate =
+        ron::from_str(r#"(name: "Old", init_state: (tile_map: {}))"#).unwrap();
+    assert_eq!(level.order, 0);
+}
+
+#[test]
+fn broken_levels_are_reported() {
+    let assets =
+        std::env::temp_dir().join(format!("learning_conrod_broken_{}", std::process::id()));
+    let levels = assets.join("levels");
+    std::fs::create_dir_all(&levels).unwrap();
+
+    let level = LevelTemplate::new("Working", LevelState::default());
+    std::fs::write(levels.join("working.ron"), ron::to_string(&level).unwrap()).unwrap();
+    std::fs::write(
+        levels.join("broken.ron"),
+        "(\n    name: \"Broken\",\n    init_state: (tile_map: {(x: 0, y: 0): Lava}),\n)",
+    )
+    .unwrap();
+    std::fs::write(levels.join(".gitkeep"), "").unwrap();
+
+    let directory = load_levels(&assets);
+    std::fs::remove_dir_all(&assets).unwrap();
+    let directory = directory.unwrap_or_else(|err| panic!("{}", err));
+
+    assert_eq!(directory.loaded.len(), 1);
+    assert_eq!(directory.broken.len(), 1);
+    let broken = &directory.broken[0];
+    assert_eq!(broken.file_name, "broken.ron");
+    match &broken.error {
+        LoadingError::Spanned(err) => assert_eq!(err.position.line, 3),
+        err => panic!("expected a parse error, found {}", err),
+    }
+    assert!(broken.error.to_string().starts_with("line 3, column "));
+}
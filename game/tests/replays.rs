@@ -0,0 +1,117 @@
+mod common;
+
+use common::{at, inputs};
+use learning_conrod_game::game::level::loading::load_levels;
+use learning_conrod_game::game::replay::{load_replays, Replay};
+use learning_conrod_game::game::simulation::TICK_DURATION;
+use learning_conrod_game::game::{Action, GameState, InputSet, LevelTemplate, TileType};
+use std::path::Path;
+use std::rc::Rc;
+
+// a corridor from x = 0 to x = 3 with a goal at the end
+fn corridor() -> LevelTemplate {
+    let mut tile_map = common::corridor(3);
+    tile_map.insert(at(3, 0), TileType::Goal { active: true });
+    common::level("Replays", tile_map)
+}
+
+// plays the level like the gui does, with frames of uneven length
+fn record(level: Rc<LevelTemplate>, frames: &[(f32, InputSet)]) -> (GameState, Replay) {
+    let mut state = GameState::new(level.clone()).unwrap();
+    let mut replay = Replay::new(&level).unwrap();
+    for (dt, inputs) in frames {
+        let ticks = state.step(inputs, *dt);
+        replay.record(inputs, ticks);
+    }
+    (state, replay)
+}
+
+#[test]
+fn replays_reproduce_the_run() {
+    let level = Rc::new(corridor());
+    let mut frames = vec![];
+    for frame in 0..200 {
+        let dt = TICK_DURATION * (1.0 + (frame % 3) as f32 * 0.7);
+        let actions = match frame % 5 {
+            0 => inputs(&[Action::Right, Action::Down]),
+            _ => inputs(&[Action::Right]),
+        };
+        frames.push((dt, actions));
+    }
+
+    let (recorded, replay) = record(level.clone(), &frames);
+
+    let serialized = ron::to_string(&replay).unwrap();
+    let loaded: Replay = ron::from_str(&serialized).unwrap();
+    assert_eq!(loaded, replay);
+
+    let played = loaded.play(level).unwrap();
+    match (recorded, played) {
+        (GameState::GameState { position: a, .. }, GameState::GameState { position: b, .. }) => {
+            assert_eq!(a, b)
+        }
+        (GameState::Won { .. }, GameState::Won { .. }) => {}
+        (a, b) => panic!("replay diverged: {a:?} vs {b:?}"),
+    }
+}
+
+#[test]
+fn replays_do_not_fit_changed_levels() {
+    let level = corridor();
+    let replay = Replay::new(&level).unwrap();
+
+    let mut changed = level.clone();
+    changed.init_state.tile_map.insert(at(0, 1), TileType::Path);
+
+    assert!(replay.play(Rc::new(level)).is_ok());
+    assert!(replay.play(Rc::new(changed)).is_err());
+}
+
+// the regression set, every replay in the fixtures has to be a solution of its level
+#[test]
+fn recorded_replays_still_win() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let levels = load_levels(&fixtures).unwrap().loaded;
+
+    let directory = load_replays(&fixtures).unwrap();
+    if let Some(broken) = directory.broken.first() {
+        panic!(
+            "the replay {} is broken: {}",
+            broken.file_name, broken.error
+        );
+    }
+    assert!(!directory.loaded.is_empty());
+
+    for replay in directory.loaded {
+        let level = levels
+            .iter()
+            .find(|level| level.name == replay.level_name)
+            .unwrap_or_else(|| panic!("no level named {}", replay.level_name));
+
+        match replay.play(Rc::new(level.clone())) {
+            Ok(GameState::Won { .. }) => {}
+            Ok(_) => panic!("the replay of {} no longer wins", replay.level_name),
+            Err(err) => panic!("{err}"),
+        }
+    }
+}
+
+#[test]
+fn broken_replays_are_reported() {
+    let assets =
+        std::env::temp_dir().join(format!("learning_conrod_replays_{}", std::process::id()));
+    let replays = assets.join("replays");
+    std::fs::create_dir_all(&replays).unwrap();
+    std::fs::write(
+        replays.join("broken.ron"),
+        "(level_name: \"Test\", inputs: [(1, [Jump])])",
+    )
+    .unwrap();
+
+    let directory = load_replays(&assets).unwrap();
+    std::fs::remove_dir_all(&assets).unwrap();
+
+    assert!(directory.loaded.is_empty());
+    assert_eq!(directory.broken.len(), 1);
+    assert_eq!(directory.broken[0].file_name, "broken.ron");
+}
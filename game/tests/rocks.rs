@@ -0,0 +1,70 @@
+mod common;
+
+use common::{at, goal_active, hold, wall};
+use learning_conrod_game::game::entity::Entity;
+use learning_conrod_game::game::{
+    Action, ButtonKind, GameState, InputSet, LevelTemplate, ObjectCoordinate, TileType,
+};
+use std::rc::Rc;
+
+const GOAL: ObjectCoordinate = ObjectCoordinate { x: 0, y: 1, z: 0 };
+
+// a corridor from x = 0 to x = 4 closed off by a wall,
+// with a rock at x = 2 and a pressure plate at x = 3 that activates a goal
+fn corridor() -> Rc<LevelTemplate> {
+    let mut tile_map = common::corridor(5);
+    tile_map.insert(
+        at(3, 0),
+        TileType::Button {
+            pressed: false,
+            inverted: false,
+            targets: vec![GOAL],
+            kind: ButtonKind::PressurePlate,
+        },
+    );
+    tile_map.insert(at(5, 0), wall());
+    tile_map.insert(GOAL, TileType::Goal { active: false });
+
+    let mut level = common::level("Rocks", tile_map);
+    level.init_state.entities.insert(at(2, 0), Entity::Rock);
+    Rc::new(level)
+}
+
+fn rocks(state: &GameState) -> Vec<ObjectCoordinate> {
+    match state {
+        GameState::GameState { level_state, .. } => level_state.entities.keys().copied().collect(),
+        _ => panic!("the game should still be running"),
+    }
+}
+
+#[test]
+fn walking_into_a_rock_pushes_it_one_tile() {
+    let mut state = GameState::new(corridor()).unwrap();
+
+    hold(&mut state, &[Action::Right], 200);
+
+    assert_eq!(rocks(&state), vec![at(3, 0)]);
+}
+
+#[test]
+fn rocks_hold_pressure_plates_down() {
+    let mut state = GameState::new(corridor()).unwrap();
+
+    hold(&mut state, &[Action::Right], 200);
+    state.tick(&InputSet::new());
+
+    assert!(goal_active(&state, GOAL));
+}
+
+#[test]
+fn rocks_cannot_be_pushed_into_walls() {
+    let mut state = GameState::new(corridor()).unwrap();
+
+    hold(&mut state, &[Action::Right], 1000);
+
+    assert_eq!(rocks(&state), vec![at(4, 0)]);
+    match state {
+        GameState::GameState { position, .. } => assert!(position.x < 3.5),
+        _ => panic!("the game should still be running"),
+    }
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use common::{at, hold, wall};
+use learning_conrod_game::game::{
+    Action, Direction, GameState, GateVisibility, InputSet, LevelTemplate, ObjectCoordinate,
+    RevealTrigger, TileType, WallType,
+};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+fn hidden_gate(reveal: RevealTrigger) -> TileType {
+    TileType::Gate {
+        open: false,
+        facing: Direction::EAST,
+        hidden: GateVisibility::Hidden(Box::new(wall())),
+        reveal,
+    }
+}
+
+fn fake_wall() -> TileType {
+    TileType::FakeWall {
+        kind: WallType::Lone,
+        reveal: RevealTrigger::Never,
+        revealed: false,
+    }
+}
+
+// a corridor starting at x = 0 with the given tiles after the start
+fn corridor(tiles: Vec<TileType>) -> Rc<LevelTemplate> {
+    let mut tile_map = BTreeMap::new();
+    tile_map.insert(at(0, 0), TileType::Start);
+    for (x, tile) in tiles.into_iter().enumerate() {
+        tile_map.insert(at(x as i64 + 1, 0), tile);
+    }
+    Rc::new(common::level("Secrets", tile_map))
+}
+
+fn secrets(state: &GameState) -> BTreeMap<ObjectCoordinate, bool> {
+    match state {
+        GameState::GameState { level_state, .. } => level_state.secrets(),
+        _ => panic!("the level should still be running"),
+    }
+}
+
+#[test]
+fn approaching_a_gate_reveals_it() {
+    let level = corridor(vec![
+        TileType::Path,
+        TileType::Path,
+        hidden_gate(RevealTrigger::Proximity { distance: 1 }),
+    ]);
+    let mut state = GameState::new(level).unwrap();
+
+    state.tick(&InputSet::new());
+    assert_eq!(secrets(&state), BTreeMap::from([(at(3, 0), false)]));
+
+    hold(&mut state, &[Action::Right], 300);
+    assert_eq!(secrets(&state), BTreeMap::from([(at(3, 0), true)]));
+}
+
+#[test]
+fn bumping_into_a_gate_reveals_it() {
+    let level = corridor(vec![TileType::Path, hidden_gate(RevealTrigger::Bump)]);
+    let mut state = GameState::new(level).unwrap();
+
+    hold(&mut state, &[Action::Right], 300);
+    assert_eq!(secrets(&state), BTreeMap::from([(at(2, 0), true)]));
+}
+
+#[test]
+fn buttons_reveal_gates_for_good() {
+    let mut gate = hidden_gate(RevealTrigger::Button);
+    gate.apply_button(true);
+    gate.apply_button(false);
+    assert!(!gate.is_hidden());
+    assert!(gate.is_solid());
+
+    let mut gate = hidden_gate(RevealTrigger::Never);
+    gate.apply_button(true);
+    gate.apply_button(false);
+    assert!(gate.is_hidden());
+    assert!(!gate.is_secret());
+}
+
+#[test]
+fn fake_walls_are_found_by_walking_through_them() {
+    let level = corridor(vec![
+        fake_wall(),
+        TileType::Goal { active: true },
+        fake_wall(),
+    ]);
+    let mut state = GameState::new(level).unwrap();
+
+    let right: InputSet = [Action::Right].into_iter().collect();
+    while let GameState::GameState { .. } = state {
+        state.tick(&right);
+    }
+
+    match state {
+        GameState::Won { result, .. } => {
+            assert_eq!(result.secrets_found, 1);
+            assert_eq!(result.secrets, 2);
+        }
+        _ => panic!("the level should be won"),
+    }
+}
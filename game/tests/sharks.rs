@@ -0,0 +1,102 @@
+mod common;
+
+use common::{at, hold, wall};
+use learning_conrod_game::game::entity::{Shark, SharkMovement};
+use learning_conrod_game::game::simulation::TICK_RATE;
+use learning_conrod_game::game::{
+    Direction, GameState, LevelState, LevelTemplate, PlayerCoordinate, TileType,
+};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+// a 7 by 3 room of paths
+fn room(sharks: Vec<Shark>) -> LevelState {
+    let mut tile_map = BTreeMap::new();
+    for x in 0..7 {
+        for y in 0..3 {
+            tile_map.insert(at(x, y), TileType::Path);
+        }
+    }
+    tile_map.insert(at(6, 2), wall());
+    LevelState {
+        tile_map,
+        sharks,
+        ..Default::default()
+    }
+}
+
+fn shark(x: f32, y: f32, facing: Direction, movement: SharkMovement) -> Shark {
+    Shark {
+        position: PlayerCoordinate { x, y, z: 0 },
+        facing,
+        speed: 2.0,
+        movement,
+    }
+}
+
+#[test]
+fn bouncing_sharks_turn_around_at_walls() {
+    let mut level = room(vec![shark(
+        4.0,
+        2.0,
+        Direction::EAST,
+        SharkMovement::Bounce,
+    )]);
+
+    for _ in 0..TICK_RATE {
+        level.update_sharks();
+    }
+
+    let shark = &level.sharks[0];
+    assert_eq!(shark.facing, Direction::WEST);
+    assert!(shark.position.x < 5.0);
+}
+
+#[test]
+fn patrolling_sharks_follow_their_waypoints() {
+    let mut level = room(vec![shark(
+        6.0,
+        0.0,
+        Direction::WEST,
+        SharkMovement::Patrol {
+            waypoints: vec![at(4, 0), at(4, 1)],
+            next: 0,
+        },
+    )]);
+
+    // two tiles at two tiles per second
+    let mut ticks = 0;
+    while matches!(
+        level.sharks[0].movement,
+        SharkMovement::Patrol { next: 0, .. }
+    ) {
+        level.update_sharks();
+        ticks += 1;
+    }
+    assert!(ticks <= TICK_RATE + 1);
+    assert_eq!(level.sharks[0].position.x, 4.0);
+    assert_eq!(level.sharks[0].position.y, 0.0);
+
+    // half way to the second waypoint
+    for _ in 0..TICK_RATE / 4 {
+        level.update_sharks();
+    }
+    assert_eq!(level.sharks[0].facing, Direction::SOUTH);
+    assert!((level.sharks[0].position.y - 0.5).abs() < 1e-3);
+}
+
+#[test]
+fn touching_a_shark_loses_the_game() {
+    let mut level = room(vec![shark(
+        4.0,
+        0.0,
+        Direction::WEST,
+        SharkMovement::Bounce,
+    )]);
+    level.tile_map.insert(at(0, 0), TileType::Start);
+    let mut state = GameState::new(Rc::new(LevelTemplate::new("Sharks", level))).unwrap();
+
+    hold(&mut state, &[], 3 * TICK_RATE);
+
+    assert!(matches!(state, GameState::Lost { .. }));
+}
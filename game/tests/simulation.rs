@@ -0,0 +1,159 @@
+mod common;
+
+use common::{at, hold, inputs};
+use learning_conrod_game::game::simulation::{TICK_DURATION, TICK_RATE};
+use learning_conrod_game::game::{
+    Action, ButtonKind, GameState, InputSet, LevelTemplate, SpawnError, TileType,
+};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+// a straight corridor starting at x = 0, with one tile per entry
+fn corridor(tiles: Vec<TileType>) -> Rc<LevelTemplate> {
+    let tile_map = tiles
+        .into_iter()
+        .enumerate()
+        .map(|(x, tile)| (at(x as i64, 0), tile))
+        .collect();
+    Rc::new(common::level("Corridor", tile_map))
+}
+
+#[test]
+fn step_runs_whole_ticks_and_keeps_the_remainder() {
+    let level = corridor(vec![TileType::Start, TileType::Path]);
+    let mut state = GameState::new(level).unwrap();
+    let none = InputSet::new();
+
+    assert_eq!(state.step(&none, TICK_DURATION * 0.6), 0);
+    assert_eq!(state.step(&none, TICK_DURATION * 0.6), 1);
+    assert_eq!(state.step(&none, TICK_DURATION * 3.0), 3);
+}
+
+#[test]
+fn ticks_are_independent_of_the_frame_rate() {
+    let level = corridor(vec![TileType::Start, TileType::Path, TileType::Path]);
+    let right = inputs(&[Action::Right]);
+
+    let mut fast = GameState::new(level.clone()).unwrap();
+    for _ in 0..120 {
+        fast.step(&right, 1.0 / 120.0);
+    }
+
+    let mut slow = GameState::new(level).unwrap();
+    for _ in 0..30 {
+        slow.step(&right, 1.0 / 30.0);
+    }
+
+    match (fast, slow) {
+        (GameState::GameState { position: a, .. }, GameState::GameState { position: b, .. }) => {
+            assert!((a.x - b.x).abs() < 1e-3)
+        }
+        _ => panic!("the game should still be running"),
+    }
+}
+
+#[test]
+fn reaching_an_active_goal_wins() {
+    let level = corridor(vec![TileType::Start, TileType::Goal { active: true }]);
+    let mut state = GameState::new(level).unwrap();
+
+    hold(&mut state, &[Action::Right], 200);
+
+    assert!(matches!(state, GameState::Won { .. }));
+}
+
+#[test]
+fn buttons_activate_their_target() {
+    let level = corridor(vec![
+        TileType::Start,
+        TileType::Button {
+            pressed: false,
+            inverted: false,
+            targets: vec![at(2, 0)],
+            kind: ButtonKind::Toggle,
+        },
+        TileType::Goal { active: false },
+    ]);
+    let mut state = GameState::new(level).unwrap();
+
+    hold(&mut state, &[Action::Right], 400);
+
+    assert!(matches!(state, GameState::Won { .. }));
+}
+
+#[test]
+fn inactive_goals_do_not_win() {
+    let level = corridor(vec![TileType::Start, TileType::Goal { active: false }]);
+    let mut state = GameState::new(level).unwrap();
+
+    hold(&mut state, &[Action::Right], 200);
+
+    assert!(matches!(state, GameState::GameState { .. }));
+}
+
+#[test]
+fn the_player_spawns_on_the_start_tile() {
+    let level = corridor(vec![TileType::Path, TileType::Path, TileType::Start]);
+    let state = GameState::new(level).unwrap();
+
+    match state {
+        GameState::GameState {
+            position,
+            old_position,
+            ..
+        } => {
+            assert_eq!(position.x, 2.0);
+            assert_eq!(old_position, at(2, 0));
+        }
+        _ => panic!("the game should be running"),
+    }
+}
+
+#[test]
+fn levels_need_exactly_one_start() {
+    let none = corridor(vec![TileType::Path]);
+    assert!(matches!(
+        GameState::new(none),
+        Err(SpawnError::NoStart { .. })
+    ));
+
+    let two = corridor(vec![TileType::Start, TileType::Path, TileType::Start]);
+    match GameState::new(two) {
+        Err(SpawnError::MultipleStarts { starts, .. }) => assert_eq!(starts.len(), 2),
+        _ => panic!("two Start tiles should be rejected"),
+    }
+}
+
+// an open room of paths around a start at the origin
+fn room(speed: f32) -> Rc<LevelTemplate> {
+    let mut tile_map = BTreeMap::new();
+    for x in -5..=5 {
+        for y in -5..=5 {
+            tile_map.insert(at(x, y), TileType::Path);
+        }
+    }
+    tile_map.insert(at(0, 0), TileType::Start);
+    let mut level = common::level("Room", tile_map);
+    level.speed = speed;
+    Rc::new(level)
+}
+
+fn distance_after_one_second(actions: &[Action]) -> f32 {
+    let mut state = GameState::new(room(2.0)).unwrap();
+    hold(&mut state, actions, TICK_RATE);
+    match state {
+        GameState::GameState { position, .. } => f32::hypot(position.x, position.y),
+        _ => panic!("the game should still be running"),
+    }
+}
+
+#[test]
+fn speed_is_measured_in_tiles_per_second() {
+    assert!((distance_after_one_second(&[Action::Right]) - 2.0).abs() < 1e-3);
+}
+
+#[test]
+fn diagonal_movement_is_not_faster() {
+    let diagonal = distance_after_one_second(&[Action::Right, Action::Down]);
+    assert!((diagonal - 2.0).abs() < 1e-3);
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::{at, hold, inputs, press, tile, wall, x};
+use learning_conrod_game::game::grid::GRID_MOVE_TICKS;
+use learning_conrod_game::game::logic::LogicNetwork;
+use learning_conrod_game::game::{
+    Action, ButtonKind, Direction, GameState, LevelTemplate, MovementMode, TileType,
+};
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+// a corridor starting at x = 0 with the given tiles after the start, closed off by a wall
+fn corridor(tiles: Vec<TileType>, movement: MovementMode) -> Rc<LevelTemplate> {
+    let mut tile_map = BTreeMap::new();
+    tile_map.insert(at(0, 0), TileType::Start);
+    for (x, tile) in tiles.into_iter().enumerate() {
+        tile_map.insert(at(x as i64 + 1, 0), tile);
+    }
+    let end = tile_map.len() as i64;
+    tile_map.insert(at(end, 0), wall());
+    let mut level = common::level("Surfaces", tile_map);
+    level.movement = movement;
+    Rc::new(level)
+}
+
+// walks right until the player leaves the start, then holds `then` for a number of ticks
+fn enter_and_hold(state: &mut GameState, then: &[Action], ticks: u32) {
+    let right = inputs(&[Action::Right]);
+    while tile(state) == at(0, 0) {
+        state.tick(&right);
+    }
+    hold(state, then, ticks);
+}
+
+#[test]
+fn ice_slides_until_blocked() {
+    let level = corridor(vec![TileType::Ice; 4], MovementMode::Free);
+    let mut state = GameState::new(level).unwrap();
+
+    // input is ignored while sliding
+    enter_and_hold(&mut state, &[Action::Left], 300);
+    assert!(tile(&state).x >= 2);
+
+    enter_and_hold(&mut state, &[], 600);
+    assert_eq!(tile(&state), at(4, 0));
+}
+
+#[test]
+fn sliding_stops_on_other_tiles() {
+    let level = corridor(
+        vec![TileType::Ice, TileType::Ice, TileType::Path, TileType::Path],
+        MovementMode::Free,
+    );
+    let mut state = GameState::new(level).unwrap();
+
+    enter_and_hold(&mut state, &[], 1200);
+
+    assert_eq!(tile(&state), at(3, 0));
+}
+
+#[test]
+fn conveyors_carry_the_player() {
+    let conveyor = TileType::Conveyor {
+        facing: Direction::EAST,
+        inverted: false,
+    };
+    let level = corridor(
+        vec![conveyor.clone(), conveyor, TileType::Path, TileType::Path],
+        MovementMode::Free,
+    );
+    let mut state = GameState::new(level).unwrap();
+
+    enter_and_hold(&mut state, &[], 1200);
+
+    assert_eq!(tile(&state), at(3, 0));
+}
+
+#[test]
+fn grid_movement_slides_tile_by_tile() {
+    let level = corridor(vec![TileType::Ice; 4], MovementMode::Grid);
+    let mut state = GameState::new(level).unwrap();
+
+    press(&mut state, Action::Right);
+    hold(&mut state, &[], 6 * GRID_MOVE_TICKS);
+
+    assert_eq!(x(&state), 4.0);
+}
+
+#[test]
+fn buttons_reverse_conveyors() {
+    let mut level = corridor(
+        vec![
+            TileType::Conveyor {
+                facing: Direction::EAST,
+                inverted: false,
+            },
+            TileType::Button {
+                pressed: true,
+                inverted: false,
+                targets: vec![at(1, 0)],
+                kind: ButtonKind::Toggle,
+            },
+        ],
+        MovementMode::Free,
+    )
+    .init_state
+    .clone();
+
+    LogicNetwork::default().update(&mut level);
+
+    let conveyor = level.tile_map.get(&at(1, 0)).unwrap();
+    assert_eq!(conveyor.effective_facing(), Some(Direction::WEST));
+}
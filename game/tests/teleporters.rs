@@ -0,0 +1,138 @@
+mod common;
+
+use common::{at, inputs, tile};
+use learning_conrod_game::game::logic::LogicNetwork;
+use learning_conrod_game::game::simulation::{TELEPORT_COOLDOWN, TICK_RATE};
+use learning_conrod_game::game::{
+    Action, ButtonKind, GameState, InputSet, LevelTemplate, ObjectCoordinate, TeleportDestination,
+    TileType,
+};
+use std::rc::Rc;
+
+fn teleporter(destination: TeleportDestination, active: bool) -> TileType {
+    TileType::Teleporter {
+        destination,
+        active,
+    }
+}
+
+// a corridor from x = 0 to x = 5 with a teleporter at x = 1 and a separate row of paths below
+fn level(first: TileType, others: Vec<(ObjectCoordinate, TileType)>) -> Rc<LevelTemplate> {
+    let mut tile_map = common::corridor(6);
+    for x in 0..6 {
+        tile_map.insert(at(x, 2), TileType::Path);
+    }
+    tile_map.insert(at(1, 0), first);
+    tile_map.extend(others);
+    Rc::new(common::level("Teleporters", tile_map))
+}
+
+// holds `action` until the player enters another tile, returns the ticks that took
+fn walk(state: &mut GameState, action: Action) -> u32 {
+    let inputs = inputs(&[action]);
+    let from = tile(state);
+    let mut ticks = 0;
+    while tile(state) == from {
+        assert!(
+            ticks < 10 * TICK_RATE,
+            "the player did not leave {:?} within 10 seconds",
+            from
+        );
+        state.tick(&inputs);
+        ticks += 1;
+    }
+    ticks
+}
+
+fn step_off_start(state: &mut GameState) {
+    walk(state, Action::Right);
+}
+
+#[test]
+fn teleporters_move_the_player_to_their_destination() {
+    let level = level(
+        teleporter(TeleportDestination::Coordinate(at(4, 2)), true),
+        vec![],
+    );
+    let mut state = GameState::new(level).unwrap();
+
+    step_off_start(&mut state);
+
+    assert_eq!(tile(&state), at(4, 2));
+}
+
+#[test]
+fn channels_link_teleporters() {
+    let channel = || TeleportDestination::Channel(String::from("blue"));
+    let level = level(
+        teleporter(channel(), true),
+        vec![(at(3, 2), teleporter(channel(), true))],
+    );
+    let mut state = GameState::new(level).unwrap();
+
+    step_off_start(&mut state);
+    assert_eq!(tile(&state), at(3, 2));
+}
+
+#[test]
+fn teleporting_needs_a_cooldown() {
+    let channel = || TeleportDestination::Channel(String::from("blue"));
+    let level = level(
+        teleporter(channel(), true),
+        vec![(at(3, 2), teleporter(channel(), true))],
+    );
+    // fast enough to step off and back on before the cooldown runs out
+    let level = Rc::new(LevelTemplate {
+        speed: 4.0,
+        ..(*level).clone()
+    });
+    let mut state = GameState::new(level).unwrap();
+    step_off_start(&mut state);
+    assert_eq!(tile(&state), at(3, 2));
+
+    let ticks = walk(&mut state, Action::Right) + walk(&mut state, Action::Left);
+    assert!(ticks < TELEPORT_COOLDOWN);
+    assert_eq!(tile(&state), at(3, 2));
+
+    walk(&mut state, Action::Right);
+    for _ in 0..TELEPORT_COOLDOWN {
+        state.tick(&InputSet::new());
+    }
+    walk(&mut state, Action::Left);
+    assert_eq!(tile(&state), at(1, 0));
+}
+
+#[test]
+fn inactive_teleporters_do_nothing() {
+    let level = level(
+        teleporter(TeleportDestination::Coordinate(at(4, 2)), false),
+        vec![],
+    );
+    let mut state = GameState::new(level).unwrap();
+
+    step_off_start(&mut state);
+
+    assert_eq!(tile(&state), at(1, 0));
+}
+
+#[test]
+fn buttons_switch_teleporters_on() {
+    let button = TileType::Button {
+        pressed: true,
+        inverted: false,
+        targets: vec![at(1, 0)],
+        kind: ButtonKind::Toggle,
+    };
+    let level = level(
+        teleporter(TeleportDestination::Coordinate(at(4, 2)), false),
+        vec![(at(5, 0), button)],
+    );
+    let mut level_state = level.init_state.clone();
+
+    LogicNetwork::default().update(&mut level_state);
+
+    assert!(matches!(
+        level_state.tile_map.get(&at(1, 0)),
+        Some(TileType::Teleporter { active: true, .. })
+    ));
+}
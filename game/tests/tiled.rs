@@ -0,0 +1,250 @@
+mod common;
+
+use common::{at, same};
+use learning_conrod_game::game::test_level::test_level;
+use learning_conrod_game::game::tiled::tmx::{from_tmx, to_tmx};
+use learning_conrod_game::game::tiled::{export, import, load_mapping, TiledMap, TiledMapping};
+use learning_conrod_game::game::{LevelTemplate, ObjectCoordinate, TileType, WallType};
+use std::path::Path;
+
+const CORRIDOR_TMJ: &str = r#"{
+    "type": "map",
+    "orientation": "orthogonal",
+    "width": 4,
+    "height": 1,
+    "tilewidth": 64,
+    "tileheight": 64,
+    "infinite": false,
+    "properties": [
+        { "name": "name", "type": "string", "value": "Corridor" },
+        { "name": "origin_x", "type": "int", "value": 2 }
+    ],
+    "tilesets": [{ "firstgid": 1, "source": "tiles.tsx" }],
+    "layers": [
+        {
+            "type": "tilelayer",
+            "id": 1,
+            "name": "Ground",
+            "width": 4,
+            "height": 1,
+            "data": [2, 1, 3, 4]
+        },
+        {
+            "type": "objectgroup",
+            "id": 2,
+            "name": "Buttons",
+            "objects": [
+                { "id": 1, "type": "button", "x": 192, "y": 0 },
+                {
+                    "id": 2,
+                    "class": "target",
+                    "x": 130,
+                    "y": 10,
+                    "properties": [{ "name": "button", "type": "object", "value": 1 }]
+                }
+            ]
+        },
+        { "type": "imagelayer", "id": 3, "name": "Background" }
+    ]
+}"#;
+
+const CORRIDOR_TMX: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" width="4" height="1" tilewidth="64" tileheight="64" infinite="0">
+ <properties>
+  <property name="name" value="Corridor"/>
+  <property name="origin_x" type="int" value="2"/>
+ </properties>
+ <tileset firstgid="1" source="tiles.tsx"/>
+ <layer id="1" name="Ground" width="4" height="1">
+  <data encoding="csv">
+2,1,3,4
+</data>
+ </layer>
+ <objectgroup id="2" name="Buttons">
+  <object id="1" class="button" x="192" y="0"/>
+  <object id="2" type="target" x="130" y="10">
+   <properties>
+    <property name="button" type="object" value="1"/>
+   </properties>
+  </object>
+ </objectgroup>
+</map>
+"#;
+
+fn mapping() -> TiledMapping {
+    TiledMapping {
+        tileset: "tiles.tsx".to_string(),
+        tiles: [
+            (0, TileType::Start),
+            (1, TileType::Path),
+            (2, TileType::Goal { active: false }),
+            (
+                3,
+                TileType::Button {
+                    pressed: false,
+                    inverted: false,
+                    targets: vec![],
+                    kind: Default::default(),
+                },
+            ),
+        ]
+        .into_iter()
+        .collect(),
+    }
+}
+
+fn assert_corridor(level: &LevelTemplate) {
+    let tiles = &level.init_state.tile_map;
+    assert_eq!(level.name, "Corridor");
+    assert_eq!(tiles.len(), 4);
+    assert_eq!(tiles.get(&at(2, 0)), Some(&TileType::Path));
+    assert_eq!(tiles.get(&at(3, 0)), Some(&TileType::Start));
+    assert_eq!(
+        tiles.get(&at(4, 0)),
+        Some(&TileType::Goal { active: false })
+    );
+    assert!(matches!(
+        tiles.get(&at(5, 0)),
+        Some(TileType::Button { targets, .. }) if targets == &vec![at(4, 0)]
+    ));
+}
+
+#[test]
+fn json_maps_are_imported() {
+    let map = TiledMap::from_tmj(CORRIDOR_TMJ).unwrap_or_else(|err| panic!("{}", err));
+    let level = import(&map, &mapping()).unwrap_or_else(|err| panic!("{}", err));
+    assert_corridor(&level);
+}
+
+#[test]
+fn xml_maps_are_imported() {
+    let map = from_tmx(CORRIDOR_TMX).unwrap_or_else(|err| panic!("{}", err));
+    let level = import(&map, &mapping()).unwrap_or_else(|err| panic!("{}", err));
+    assert_corridor(&level);
+}
+
+#[test]
+fn levels_round_trip_through_json_and_xml() {
+    let mut level = test_level();
+    // a button on another floor and a tile the mapping does not know
+    level.init_state.tile_map.insert(
+        ObjectCoordinate { x: -1, y: 3, z: 1 },
+        TileType::Button {
+            pressed: false,
+            inverted: false,
+            targets: vec![at(0, 0), ObjectCoordinate { x: 2, y: 2, z: 1 }],
+            kind: Default::default(),
+        },
+    );
+    level.init_state.tile_map.insert(
+        at(7, -2),
+        TileType::Wall {
+            kind: WallType::Lone,
+        },
+    );
+
+    let map = export(&level, &mapping()).unwrap();
+    let json = TiledMap::from_tmj(&map.to_tmj().unwrap()).unwrap();
+    let xml = from_tmx(&to_tmx(&map)).unwrap_or_else(|err| panic!("{}", err));
+    assert_eq!(json, map);
+    assert_eq!(xml, map);
+
+    let imported = import(&map, &mapping()).unwrap_or_else(|err| panic!("{}", err));
+    assert!(same(&imported, &level));
+}
+
+#[test]
+fn targets_on_floors_without_tiles_are_exported() {
+    let mut level = test_level();
+    let upstairs = ObjectCoordinate { x: 1, y: 1, z: 2 };
+    if let Some(TileType::Button { targets, .. }) = level
+        .init_state
+        .tile_map
+        .get_mut(&ObjectCoordinate { x: -1, y: -1, z: 0 })
+    {
+        targets.push(upstairs);
+    }
+
+    let map = export(&level, &mapping()).unwrap();
+    let imported = import(&map, &mapping()).unwrap_or_else(|err| panic!("{}", err));
+    assert!(same(&imported, &level));
+}
+
+#[test]
+fn object_layers_belong_to_the_floor_before_them() {
+    let floors = CORRIDOR_TMJ.replace(
+        r#"{ "type": "imagelayer", "id": 3, "name": "Background" }"#,
+        r#"{
+            "type": "tilelayer",
+            "id": 3,
+            "name": "Upstairs",
+            "width": 4,
+            "height": 1,
+            "data": [2, 2, 2, 4]
+        },
+        {
+            "type": "objectgroup",
+            "id": 4,
+            "name": "Upstairs buttons",
+            "objects": [
+                { "id": 3, "type": "button", "x": 192, "y": 0 },
+                {
+                    "id": 4,
+                    "type": "target",
+                    "x": 0,
+                    "y": 0,
+                    "properties": [{ "name": "button", "type": "object", "value": 3 }]
+                }
+            ]
+        }"#,
+    );
+    let map = TiledMap::from_tmj(&floors).unwrap();
+    let level = import(&map, &mapping()).unwrap_or_else(|err| panic!("{}", err));
+
+    let button = ObjectCoordinate { x: 5, y: 0, z: 1 };
+    assert!(matches!(
+        level.init_state.tile_map.get(&button),
+        Some(TileType::Button { targets, .. })
+            if targets == &vec![ObjectCoordinate { x: 2, y: 0, z: 1 }]
+    ));
+}
+
+#[test]
+fn only_tiles_of_the_mapped_tileset_are_imported() {
+    let other = CORRIDOR_TMJ.replace(
+        r#""tilesets": [{ "firstgid": 1, "source": "tiles.tsx" }]"#,
+        r#""tilesets": [{ "firstgid": 1, "source": "other.tsx" }, { "firstgid": 3, "source": "../tiled/tiles.tsx" }]"#,
+    );
+    let map = TiledMap::from_tmj(&other).unwrap();
+    let err = import(&map, &mapping()).unwrap_err();
+    assert_eq!(err.to_string(), "tile 2 in layer Ground has no mapping");
+
+    // gid 3 is the first tile of tiles.tsx
+    let shifted = other.replace("[2, 1, 3, 4]", "[4, 3, 5, 6]");
+    let map = TiledMap::from_tmj(&shifted).unwrap();
+    let level = import(&map, &mapping()).unwrap_or_else(|err| panic!("{}", err));
+    assert_corridor(&level);
+
+    let missing = CORRIDOR_TMJ.replace("tiles.tsx", "other.tsx");
+    let map = TiledMap::from_tmj(&missing).unwrap();
+    assert!(import(&map, &mapping()).is_err());
+}
+
+#[test]
+fn infinite_maps_and_unmapped_tiles_are_rejected() {
+    let mut map = TiledMap::from_tmj(CORRIDOR_TMJ).unwrap();
+    map.infinite = true;
+    assert!(import(&map, &mapping()).is_err());
+
+    let unmapped = CORRIDOR_TMJ.replace("[2, 1, 3, 4]", "[2, 1, 3, 9]");
+    let map = TiledMap::from_tmj(&unmapped).unwrap();
+    let err = import(&map, &mapping()).unwrap_err();
+    assert_eq!(err.to_string(), "tile 9 in layer Ground has no mapping");
+}
+
+#[test]
+fn the_shipped_mapping_loads() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets/tiled/mapping.ron");
+    let mapping = load_mapping(&path).unwrap_or_else(|err| panic!("{}", err));
+    assert_eq!(mapping.tiles.len(), 13);
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use common::{at, hold, tile, walk_to};
+use learning_conrod_game::game::entity::Entity;
+use learning_conrod_game::game::undo::{REWIND_DELAY, REWIND_INTERVAL};
+use learning_conrod_game::game::{Action, ButtonKind, GameState, LevelTemplate, TileType};
+use std::rc::Rc;
+
+// a corridor from x = 0 to x = 5 with a toggle button at x = 1 and a rock at x = 3
+fn corridor() -> Rc<LevelTemplate> {
+    let mut tile_map = common::corridor(6);
+    tile_map.insert(
+        at(1, 0),
+        TileType::Button {
+            pressed: false,
+            inverted: false,
+            targets: vec![],
+            kind: ButtonKind::Toggle,
+        },
+    );
+
+    let mut level = common::level("Undo", tile_map);
+    level.init_state.entities.insert(at(3, 0), Entity::Rock);
+    Rc::new(level)
+}
+
+fn button_pressed(state: &GameState) -> bool {
+    match state {
+        GameState::GameState { level_state, .. } => matches!(
+            level_state.tile_map.get(&at(1, 0)),
+            Some(TileType::Button { pressed: true, .. })
+        ),
+        _ => panic!("the game should still be running"),
+    }
+}
+
+#[test]
+fn undo_restores_the_player_and_the_level() {
+    let mut state = GameState::new(corridor()).unwrap();
+
+    walk_to(&mut state, 2);
+    assert!(button_pressed(&state));
+
+    assert!(state.undo());
+    assert_eq!(tile(&state), at(1, 0));
+    assert!(button_pressed(&state));
+
+    assert!(state.undo());
+    assert_eq!(tile(&state), at(0, 0));
+    assert!(!button_pressed(&state));
+
+    assert!(!state.undo());
+    match state {
+        GameState::GameState { moves, .. } => {
+            assert_eq!(moves.moves, 0);
+            assert_eq!(moves.undos, 2);
+        }
+        _ => panic!("the game should still be running"),
+    }
+}
+
+#[test]
+fn pushing_a_rock_is_undone_with_the_move() {
+    let mut state = GameState::new(corridor()).unwrap();
+
+    walk_to(&mut state, 3);
+    state.undo();
+
+    assert_eq!(tile(&state), at(2, 0));
+    match state {
+        GameState::GameState { level_state, .. } => {
+            assert_eq!(
+                level_state.entities.keys().copied().collect::<Vec<_>>(),
+                vec![at(3, 0)]
+            )
+        }
+        _ => panic!("the game should still be running"),
+    }
+}
+
+#[test]
+fn holding_undo_rewinds_continuously() {
+    let mut state = GameState::new(corridor()).unwrap();
+    walk_to(&mut state, 4);
+
+    hold(&mut state, &[Action::Undo], 1);
+    assert_eq!(tile(&state), at(3, 0));
+
+    // a single press only undoes once
+    hold(&mut state, &[Action::Undo], REWIND_DELAY - 1);
+    assert_eq!(tile(&state), at(3, 0));
+
+    hold(&mut state, &[Action::Undo], REWIND_INTERVAL + 1);
+    assert_eq!(tile(&state), at(1, 0));
+}
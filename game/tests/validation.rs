@@ -0,0 +1,189 @@
+mod common;
+
+use common::at;
+use learning_conrod_game::game::logic::Signal;
+use learning_conrod_game::game::test_level::test_level;
+use learning_conrod_game::game::validation::{validate, Diagnostic, Severity};
+use learning_conrod_game::game::{
+    ButtonKind, Direction, GateVisibility, LevelState, LevelTemplate, ObjectCoordinate,
+    Orientation, RevealTrigger, TileType, WallType,
+};
+use std::collections::BTreeMap;
+
+fn level(tiles: Vec<(ObjectCoordinate, TileType)>) -> LevelTemplate {
+    common::level("Validation", tiles.into_iter().collect())
+}
+
+fn wall(kind: WallType) -> TileType {
+    TileType::Wall { kind }
+}
+
+fn located(diagnostics: &[Diagnostic]) -> Vec<(Severity, Option<ObjectCoordinate>)> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.severity, diagnostic.coordinate))
+        .collect()
+}
+
+#[test]
+fn a_valid_level_has_no_diagnostics() {
+    let level = level(vec![
+        (at(0, 0), TileType::Start),
+        (at(1, 0), TileType::Goal { active: true }),
+    ]);
+    assert_eq!(validate(&level), vec![]);
+}
+
+// the level written on startup has to pass its own checks
+#[test]
+fn the_test_level_is_valid() {
+    assert_eq!(validate(&test_level()), vec![]);
+}
+
+#[test]
+fn levels_need_one_start_and_a_goal() {
+    let empty = level(vec![]);
+    assert_eq!(
+        located(&validate(&empty)),
+        vec![(Severity::Error, None), (Severity::Error, None)]
+    );
+
+    let two_starts = level(vec![
+        (at(0, 0), TileType::Start),
+        (at(1, 0), TileType::Start),
+        (at(2, 0), TileType::Goal { active: true }),
+    ]);
+    assert_eq!(
+        located(&validate(&two_starts)),
+        vec![(Severity::Error, Some(at(1, 0)))]
+    );
+}
+
+#[test]
+fn buttons_have_to_target_signal_sinks() {
+    let mut level = level(vec![
+        (at(0, 0), TileType::Start),
+        (at(1, 0), TileType::Goal { active: false }),
+        (
+            at(2, 0),
+            TileType::Button {
+                pressed: false,
+                inverted: false,
+                targets: vec![at(1, 0), at(0, 0), at(9, 9)],
+                kind: ButtonKind::Toggle,
+            },
+        ),
+    ]);
+    level.logic.sinks.insert(at(0, 0), Signal::Button(at(2, 0)));
+
+    assert_eq!(
+        located(&validate(&level)),
+        vec![
+            (Severity::Error, Some(at(0, 0))),
+            (Severity::Error, Some(at(2, 0))),
+            (Severity::Error, Some(at(2, 0))),
+        ]
+    );
+}
+
+#[test]
+fn hidden_gates_should_not_mimic_gates() {
+    let gate = |hidden| TileType::Gate {
+        open: false,
+        facing: Direction::NORTH,
+        hidden,
+        reveal: RevealTrigger::Never,
+    };
+    let level = level(vec![
+        (at(0, 0), TileType::Start),
+        (at(1, 0), TileType::Goal { active: true }),
+        (
+            at(2, 0),
+            gate(GateVisibility::Hidden(Box::new(gate(
+                GateVisibility::Visible,
+            )))),
+        ),
+        (
+            at(3, 0),
+            gate(GateVisibility::Hidden(Box::new(TileType::Path))),
+        ),
+    ]);
+    assert_eq!(
+        located(&validate(&level)),
+        vec![(Severity::Warning, Some(at(2, 0)))]
+    );
+}
+
+#[test]
+fn walls_have_to_match_their_neighbours() {
+    let horizontal = WallType::Double {
+        orientation: Orientation::Horizontal,
+    };
+    let level = level(vec![
+        (at(0, 0), TileType::Start),
+        (at(0, 1), TileType::Goal { active: true }),
+        (
+            at(1, 1),
+            wall(WallType::End {
+                facing: Direction::WEST,
+            }),
+        ),
+        (at(2, 1), wall(horizontal)),
+        (at(3, 1), wall(horizontal)),
+        (at(5, 1), wall(horizontal)),
+    ]);
+    let diagnostics = validate(&level);
+    assert_eq!(
+        located(&diagnostics),
+        vec![
+            (Severity::Warning, Some(at(3, 1))),
+            (Severity::Warning, Some(at(5, 1))),
+        ]
+    );
+    assert!(diagnostics[1].message.contains("Lone"));
+}
+
+#[test]
+fn wall_types_follow_their_neighbours() {
+    let from =
+        |walls: &[Direction]| WallType::from_neighbours(|direction| walls.contains(&direction));
+
+    assert_eq!(from(&[]), WallType::Lone);
+    assert_eq!(
+        from(&[
+            Direction::NORTH,
+            Direction::EAST,
+            Direction::SOUTH,
+            Direction::WEST
+        ]),
+        WallType::Center
+    );
+    assert_eq!(
+        from(&[Direction::NORTH, Direction::SOUTH]),
+        WallType::Double {
+            orientation: Orientation::Vertical
+        }
+    );
+    assert_eq!(
+        from(&[Direction::NORTH, Direction::EAST, Direction::WEST]),
+        WallType::Single {
+            facing: Direction::SOUTH
+        }
+    );
+    assert_eq!(
+        from(&[Direction::SOUTH]),
+        WallType::End {
+            facing: Direction::NORTH
+        }
+    );
+}
+
+#[test]
+fn diagnostics_name_their_location() {
+    let level = level(vec![(at(0, 0), TileType::Start)]);
+    let messages: BTreeMap<_, _> = validate(&level)
+        .into_iter()
+        .map(|diagnostic| (diagnostic.coordinate, diagnostic.to_string()))
+        .collect();
+    assert_eq!(messages[&None], "error: there is no Goal tile");
+}